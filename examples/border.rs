@@ -9,7 +9,7 @@ fn main() {
 }
 
 fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 
     let event = TextPopupEvent {
         content: "Border Example".to_string(),
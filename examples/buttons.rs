@@ -1,5 +1,7 @@
 use bevy::prelude::*;
-use bevy_text_popup::{TextPopupButton, TextPopupEvent, TextPopupLocation, TextPopupPlugin};
+use bevy_text_popup::{
+    TextPopupAction, TextPopupButton, TextPopupEvent, TextPopupLocation, TextPopupPlugin,
+};
 
 fn main() {
     App::new()
@@ -9,7 +11,10 @@ fn main() {
 }
 
 fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
+
+    // Button actions can be one-shot systems with full access to system params.
+    let generate_new_popup = commands.register_system(generate_new_popup);
 
     let event = TextPopupEvent {
         content: "Close this popup and generate a new one at the bottom?".to_string(),
@@ -17,18 +22,7 @@ fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupE
             text: "OK".to_string(),
             text_color: Color::BLACK.into(),
             background_color: Color::WHITE.into(),
-            action: |commands, root_entity| {
-                // Fire event to spawn a new popup when user clicks 'OK'.
-                commands.queue(|world: &mut World| {
-                    world.send_event(TextPopupEvent {
-                        content: "New Popup Generated".to_string(),
-                        location: TextPopupLocation::Bottom,
-                        ..Default::default()
-                    });
-                });
-                // Despawn the original popup.
-                commands.entity(root_entity).despawn();
-            },
+            action: TextPopupAction::system(generate_new_popup),
             ..Default::default()
         }),
         dismiss_button: Some(TextPopupButton {
@@ -40,4 +34,37 @@ fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupE
         ..default()
     };
     text_popup_events.write(event);
+
+    // Or closures capturing whatever they need.
+    let item_id = 42;
+    text_popup_events.write(TextPopupEvent {
+        content: format!("Delete item #{}?", item_id),
+        location: TextPopupLocation::Top,
+        confirm_button: Some(TextPopupButton {
+            text: "Delete".to_string(),
+            text_color: Color::BLACK.into(),
+            background_color: Color::WHITE.into(),
+            action: TextPopupAction::new(move |commands, root_entity| {
+                info!("Deleting item #{}", item_id);
+                commands.entity(root_entity).despawn();
+            }),
+            ..Default::default()
+        }),
+        ..default()
+    });
+}
+
+fn generate_new_popup(
+    In(root_entity): In<Entity>,
+    mut commands: Commands,
+    mut text_popup_events: MessageWriter<TextPopupEvent>,
+) {
+    // Fire event to spawn a new popup when user clicks 'OK'.
+    text_popup_events.write(TextPopupEvent {
+        content: "New Popup Generated".to_string(),
+        location: TextPopupLocation::Bottom,
+        ..Default::default()
+    });
+    // Despawn the original popup.
+    commands.entity(root_entity).despawn();
 }
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupButton, TextPopupChoices, TextPopupEvent, TextPopupLocation, TextPopupPlugin,
+    TextPopupSelected, TextPopupSelection,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, log_selection)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Choose difficulty".to_string(),
+        location: TextPopupLocation::Left,
+        choices: Some(TextPopupChoices {
+            options: vec!["Easy".to_string(), "Normal".to_string(), "Hard".to_string()],
+            selected: vec![1],
+            ..default()
+        }),
+        confirm_button: Some(TextPopupButton {
+            text: "Start".to_string(),
+            ..default()
+        }),
+        name: Some(Name::new("Difficulty")),
+        ..default()
+    });
+
+    // Arrow keys or the D-pad move between the options and the button, Enter or South toggles.
+    text_popup_events.write(TextPopupEvent {
+        content: "Pick your rewards".to_string(),
+        location: TextPopupLocation::Right,
+        choices: Some(TextPopupChoices {
+            options: ["Gold", "Potion", "Map", "Key"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            selection: TextPopupSelection::Multiple,
+            ..default()
+        }),
+        confirm_button: Some(TextPopupButton {
+            text: "Take".to_string(),
+            ..default()
+        }),
+        // Skipping doesn't report the selection.
+        dismiss_button: Some(TextPopupButton {
+            text: "Skip".to_string(),
+            ..default()
+        }),
+        name: Some(Name::new("Rewards")),
+        ..default()
+    });
+}
+
+fn log_selection(mut selected_events: MessageReader<TextPopupSelected>, names: Query<&Name>) {
+    for selected in selected_events.read() {
+        let name = names.get(selected.popup).map(Name::as_str).unwrap_or("?");
+        info!("{}: confirmed with {:?} selected", name, selected.selected);
+    }
+}
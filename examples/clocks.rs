@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupClock, TextPopupEvent, TextPopupLocation, TextPopupPlugin, TextPopupTimeout,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_pause)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    // Freezes along with the game while virtual time is paused.
+    text_popup_events.write(TextPopupEvent {
+        content: "In-game hint (10s of game time)\nPress P to pause".to_string(),
+        location: TextPopupLocation::Bottom,
+        timeout: TextPopupTimeout::Seconds(10),
+        clock: TextPopupClock::Virtual,
+        ..default()
+    });
+}
+
+fn toggle_pause(
+    mut time: ResMut<Time<Virtual>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut text_popup_events: MessageWriter<TextPopupEvent>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    if time.is_paused() {
+        time.unpause();
+    } else {
+        time.pause();
+        // Counts in real time so it goes away even though the game is paused.
+        text_popup_events.write(TextPopupEvent {
+            content: "Game Paused".to_string(),
+            location: TextPopupLocation::Top,
+            timeout: TextPopupTimeout::Seconds(2),
+            clock: TextPopupClock::Real,
+            ..default()
+        });
+    }
+}
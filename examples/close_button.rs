@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupCloseButton, TextPopupCloseReason, TextPopupClosed, TextPopupEvent, TextPopupPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, log_closed)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Patch notes\n\n- Fixed the jump bug\n- Added a new map".to_string(),
+        padding: UiRect::all(Val::Px(24.)),
+        close_button: Some(TextPopupCloseButton::default()),
+        ..default()
+    });
+}
+
+fn log_closed(mut closed_events: MessageReader<TextPopupClosed>) {
+    for closed in closed_events.read() {
+        if closed.reason == TextPopupCloseReason::Closed {
+            info!("Patch notes closed with the X button");
+        }
+    }
+}
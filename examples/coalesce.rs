@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupCoalesce, TextPopupEvent, TextPopupLocation, TextPopupPlugin, TextPopupTimeout,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, pick_up_item)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn(Text::new("Press Space to pick up an item"));
+}
+
+fn pick_up_item(
+    mut text_popup_events: MessageWriter<TextPopupEvent>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        // Repeated messages bump a counter on the live popup instead of spawning new ones.
+        text_popup_events.write(TextPopupEvent {
+            content: "Inventory full".to_string(),
+            location: TextPopupLocation::Bottom,
+            timeout: TextPopupTimeout::Seconds(2),
+            coalesce: Some(TextPopupCoalesce::Content),
+            ..default()
+        });
+    }
+}
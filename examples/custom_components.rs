@@ -26,7 +26,7 @@ fn main() {
 }
 
 fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 
     // Example 1: Gamepad tip with custom marker component
     text_popup_events.write(TextPopupEvent {
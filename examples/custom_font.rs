@@ -13,7 +13,7 @@ fn setup(
     asset_server: Res<AssetServer>,
     mut text_popup_events: MessageWriter<TextPopupEvent>,
 ) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 
     text_popup_events.write(TextPopupEvent {
         content: "Custom Font Example".to_string(),
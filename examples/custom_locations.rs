@@ -9,7 +9,7 @@ fn main() {
 }
 
 fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 
     let custom_positions = vec![(100.0, 100.0), (300.0, 200.0), (500.0, 300.0)];
 
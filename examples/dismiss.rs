@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupDismissTrigger, TextPopupEvent, TextPopupLocation, TextPopupPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Click me to dismiss".to_string(),
+        location: TextPopupLocation::TopLeft,
+        dismiss_on: TextPopupDismissTrigger::ClickPopup,
+        ..default()
+    });
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Press any key to dismiss".to_string(),
+        location: TextPopupLocation::TopRight,
+        dismiss_on: TextPopupDismissTrigger::AnyKey,
+        ..default()
+    });
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Click outside to dismiss".to_string(),
+        modal: Some(Color::linear_rgba(0., 0., 0., 0.5).into()),
+        dismiss_on: TextPopupDismissTrigger::ClickOutside,
+        ..default()
+    });
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bevy_text_popup::{TextPopupButton, TextPopupEvent, TextPopupPlugin, TextPopupSuppression};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        // Ticked checkboxes are remembered across runs.
+        .insert_resource(
+            TextPopupSuppression::default()
+                .with_path(std::env::temp_dir().join("bevy_text_popup_suppressed_popups.txt")),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, (show_hint, reset_hints))
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn(Text::new("Press H for a hint, R to show all hints again"));
+}
+
+fn show_hint(
+    mut text_popup_events: MessageWriter<TextPopupEvent>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        text_popup_events.write(TextPopupEvent {
+            content: "Hold Shift to sprint".to_string(),
+            suppress_key: Some("hint_sprint".to_string()),
+            confirm_button: Some(TextPopupButton {
+                text: "Got it".to_string(),
+                ..default()
+            }),
+            ..default()
+        });
+    }
+}
+
+fn reset_hints(
+    mut text_popup_suppression: ResMut<TextPopupSuppression>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        text_popup_suppression.reset();
+        info!("All hints will be shown again");
+    }
+}
@@ -1,16 +1,20 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
-use bevy_text_popup::{TextPopupEvent, TextPopupLocation, TextPopupPlugin, TextPopupTimeout};
+use bevy_text_popup::{
+    TextPopupEvent, TextPopupExpires, TextPopupLocation, TextPopupPlugin, TextPopupTimeout,
+};
 
 fn main() {
     App::new()
         .add_plugins((DefaultPlugins, TextPopupPlugin))
         .add_systems(Startup, setup)
-        .add_systems(Update, spawn_new_popups)
+        .add_systems(Update, (spawn_new_popups, extend_popups))
         .run();
 }
 
 fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 
     text_popup_events.write(TextPopupEvent {
         content: "I'll disappear after 60 frames".to_string(),
@@ -55,15 +59,39 @@ fn spawn_new_popups(
     if keyboard_input.just_pressed(KeyCode::Space) {
         // Create a text popup that expires after 2 seconds when space is pressed
         text_popup_events.write(TextPopupEvent {
-            content: "Space pressed! I'll disappear after 2 seconds".to_string(),
+            content: "Space pressed! I'll disappear after 2 seconds (unless hovered)".to_string(),
             text_font: TextFont {
                 font_size: 24.0,
                 ..Default::default()
             },
             location: TextPopupLocation::Right,
             timeout: TextPopupTimeout::Seconds(2),
+            pause_on_hover: true,
             background_color: LinearRgba::new(0.0, 0.0, 1.0, 0.7).into(),
             ..default()
         });
     }
+
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        // Sub-second combat toast.
+        text_popup_events.write(TextPopupEvent {
+            content: "Critical hit!".to_string(),
+            location: TextPopupLocation::Left,
+            timeout: TextPopupTimeout::Duration(Duration::from_millis(750)),
+            ..default()
+        });
+    }
+}
+
+// Time-based timeouts can be inspected and changed while the popup is alive.
+fn extend_popups(
+    mut text_popups: Query<&mut TextPopupExpires>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyE) {
+        for mut expires in text_popups.iter_mut() {
+            expires.extend(Duration::from_secs(2));
+            info!("Popup now expires in {:?}", expires.remaining());
+        }
+    }
 }
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use bevy_text_popup::{TextPopupButton, TextPopupButtonPressed, TextPopupEvent, TextPopupPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, log_choice)
+        .run();
+}
+
+fn button(text: &str) -> TextPopupButton {
+    TextPopupButton {
+        text: text.to_string(),
+        padding: UiRect::horizontal(Val::Px(10.)),
+        focus_outline: Outline::new(Val::Px(3.), Val::Px(2.), Color::srgb(1., 0.8, 0.)),
+        ..default()
+    }
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    // Arrow keys or the D-pad move the focus ring, Enter or South activates the focused button.
+    text_popup_events.write(TextPopupEvent {
+        content: "Quit to the main menu?".to_string(),
+        confirm_button: Some(button("Quit")),
+        buttons: vec![button("Save and Quit")],
+        dismiss_button: Some(button("Cancel")),
+        default_focus: Some(2),
+        ..default()
+    });
+}
+
+fn log_choice(mut button_pressed_events: MessageReader<TextPopupButtonPressed>) {
+    for pressed in button_pressed_events.read() {
+        info!("Button #{} pressed", pressed.button_index);
+    }
+}
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupButton, TextPopupCloseReason, TextPopupClosed, TextPopupEvent, TextPopupLocation,
+    TextPopupPlugin, TextPopupSpawned, TextPopupTimeout,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, log_popup_lifecycle)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Read the tutorial, then press 'Got it'".to_string(),
+        timeout: TextPopupTimeout::Seconds(5),
+        confirm_button: Some(TextPopupButton {
+            text: "Got it".to_string(),
+            ..Default::default()
+        }),
+        name: Some(Name::new("tutorial")),
+        ..default()
+    });
+
+    text_popup_events.write(TextPopupEvent {
+        content: "I'll disappear after 120 frames".to_string(),
+        location: TextPopupLocation::Bottom,
+        timeout: TextPopupTimeout::Frames(120),
+        ..default()
+    });
+}
+
+fn log_popup_lifecycle(
+    mut spawned_events: MessageReader<TextPopupSpawned>,
+    mut closed_events: MessageReader<TextPopupClosed>,
+) {
+    for spawned in spawned_events.read() {
+        info!("Popup spawned: {:?} ({:?})", spawned.entity, spawned.name);
+    }
+    for closed in closed_events.read() {
+        match closed.reason {
+            TextPopupCloseReason::Confirmed => info!("The player acknowledged the tutorial"),
+            TextPopupCloseReason::TimedOut => info!("The tutorial timed out"),
+            reason => info!("Popup {:?} closed: {:?}", closed.entity, reason),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupButton, TextPopupButtonStyle, TextPopupCloseReason, TextPopupClosed, TextPopupEvent,
+    TextPopupLocation, TextPopupPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, log_choice)
+        .run();
+}
+
+fn button(text: &str) -> TextPopupButton {
+    TextPopupButton {
+        text: text.to_string(),
+        text_color: Color::BLACK.into(),
+        background_color: Color::WHITE.into(),
+        padding: UiRect::horizontal(Val::Px(10.)),
+        hovered: TextPopupButtonStyle {
+            background_color: Some(Color::srgb(0.8, 0.8, 0.8).into()),
+            scale: Some(1.1),
+            ..default()
+        },
+        pressed: TextPopupButtonStyle {
+            background_color: Some(Color::srgb(0.5, 0.5, 0.5).into()),
+            text_color: Some(Color::WHITE.into()),
+            scale: Some(0.95),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Save changes before quitting?".to_string(),
+        confirm_button: Some(button("Save")),
+        buttons: vec![button("Don't Save")],
+        dismiss_button: Some(button("Cancel")),
+        ..default()
+    });
+
+    // A vertical list of choices.
+    text_popup_events.write(TextPopupEvent {
+        content: "Pick a class".to_string(),
+        buttons: ["Warrior", "Mage", "Rogue", "Cleric", "Ranger"]
+            .into_iter()
+            .map(button)
+            .collect(),
+        action_direction: FlexDirection::Column,
+        location: TextPopupLocation::Left,
+        ..default()
+    });
+}
+
+fn log_choice(mut closed_events: MessageReader<TextPopupClosed>) {
+    for closed in closed_events.read() {
+        match closed.reason {
+            TextPopupCloseReason::Confirmed => info!("Saved"),
+            TextPopupCloseReason::Dismissed => info!("Cancelled"),
+            TextPopupCloseReason::Button(index) => info!("Picked button #{}", index),
+            reason => info!("Closed: {:?}", reason),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupButton, TextPopupEvent, TextPopupPlugin, TextPopupQueue, TextPopupQueueOrder,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .insert_resource(TextPopupQueue::new(TextPopupQueueOrder::Priority))
+        .add_systems(Startup, setup)
+        .add_systems(Update, clear_queue)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    // All three are sent in the same frame, but only one is shown at a time.
+    for (content, priority) in [
+        ("Daily reward collected", 0),
+        ("Server restarting in 5 minutes", 10),
+        ("New achievement unlocked", 0),
+    ] {
+        text_popup_events.write(TextPopupEvent {
+            content: format!("{}\n(press C to clear the queue)", content),
+            modal: Some(Color::linear_rgba(0., 0., 0., 0.75).into()),
+            dismiss_button: Some(TextPopupButton {
+                text: "Close".to_string(),
+                text_color: Color::BLACK.into(),
+                background_color: Color::WHITE.into(),
+                ..default()
+            }),
+            queue: true,
+            priority,
+            ..default()
+        });
+    }
+}
+
+fn clear_queue(
+    mut text_popup_queue: ResMut<TextPopupQueue>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        info!("Clearing {} queued popups", text_popup_queue.len());
+        text_popup_queue.clear();
+    }
+}
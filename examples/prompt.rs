@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupButton, TextPopupCommandsExt, TextPopupEvent, TextPopupPlugin, TextPopupPrompt,
+    TextPopupPromptResult, TextPopupTimeout,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (ask_to_overwrite, check_answer))
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn(Text::new("Press S to save"));
+}
+
+fn ask_to_overwrite(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    prompts: Query<(), With<TextPopupPrompt>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyS) || !prompts.is_empty() {
+        return;
+    }
+    let prompt = commands.prompt_text_popup(TextPopupEvent {
+        content: "Overwrite save?".to_string(),
+        confirm_button: Some(TextPopupButton {
+            text: "Yes".to_string(),
+            tag: Some("yes".to_string()),
+            ..default()
+        }),
+        dismiss_button: Some(TextPopupButton {
+            text: "No".to_string(),
+            tag: Some("no".to_string()),
+            ..default()
+        }),
+        timeout: TextPopupTimeout::Seconds(10),
+        ..default()
+    });
+    // Keep the handle around and poll it every frame.
+    commands.spawn(prompt);
+}
+
+fn check_answer(mut commands: Commands, prompts: Query<(Entity, &TextPopupPrompt)>) {
+    for (entity, prompt) in &prompts {
+        let Some(result) = prompt.result() else {
+            continue;
+        };
+        match result {
+            TextPopupPromptResult::Button { tag, .. } if tag.as_deref() == Some("yes") => {
+                info!("Save overwritten")
+            },
+            TextPopupPromptResult::Button { .. } => info!("Kept the old save"),
+            TextPopupPromptResult::Dismissed => info!("Prompt dismissed"),
+            TextPopupPromptResult::TimedOut => info!("Prompt timed out"),
+        }
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupAction, TextPopupButton, TextPopupButtonEnabled, TextPopupEvent, TextPopupPlugin,
+};
+
+const PRICE: u32 = 30;
+
+#[derive(Resource, Default)]
+struct Gold(u32);
+
+#[derive(Component)]
+struct GoldText;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .init_resource::<Gold>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (earn_gold, show_gold))
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+    commands.spawn((Text::default(), GoldText));
+
+    let can_afford = commands.register_system(|gold: Res<Gold>| gold.0 >= PRICE);
+    let buy = commands.register_system(|In(_popup): In<Entity>, mut gold: ResMut<Gold>| {
+        gold.0 -= PRICE;
+        info!("Bought a sword");
+    });
+    text_popup_events.write(TextPopupEvent {
+        content: format!("Sword: {} gold", PRICE),
+        confirm_button: Some(TextPopupButton {
+            text: "Buy".to_string(),
+            // Greyed out and unclickable until there is enough gold.
+            enabled: TextPopupButtonEnabled::When(can_afford),
+            action: TextPopupAction::system(buy),
+            ..default()
+        }),
+        dismiss_button: Some(TextPopupButton {
+            text: "Leave".to_string(),
+            ..default()
+        }),
+        ..default()
+    });
+}
+
+fn earn_gold(keyboard_input: Res<ButtonInput<KeyCode>>, mut gold: ResMut<Gold>) {
+    if keyboard_input.just_pressed(KeyCode::KeyG) {
+        gold.0 += 10;
+    }
+}
+
+fn show_gold(gold: Res<Gold>, mut text: Single<&mut Text, With<GoldText>>) {
+    text.0 = format!("Gold: {} (press G to earn 10)", gold.0);
+}
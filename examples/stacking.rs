@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupCapacity, TextPopupCapacityPolicy, TextPopupEvent, TextPopupLocation, TextPopupPlugin,
+    TextPopupStackOrder, TextPopupStacking, TextPopupTimeout,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .insert_resource(TextPopupStacking {
+            spacing: 10.,
+            order: TextPopupStackOrder::NewestFirst,
+            ..default()
+        })
+        // Never show more than 5 toasts at the top, dropping the oldest one when full.
+        .insert_resource(
+            TextPopupCapacity::new(TextPopupCapacityPolicy::DropOldest)
+                .with_location_limit(TextPopupLocation::Top, 5),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, spawn_toasts)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn(Text::new(
+        "Press T for a toast at the top, B for one at the bottom right",
+    ));
+}
+
+fn spawn_toasts(
+    mut text_popup_events: MessageWriter<TextPopupEvent>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut count: Local<u32>,
+) {
+    let location = if keyboard_input.just_pressed(KeyCode::KeyT) {
+        TextPopupLocation::Top
+    } else if keyboard_input.just_pressed(KeyCode::KeyB) {
+        TextPopupLocation::BottomRight
+    } else {
+        return;
+    };
+    *count += 1;
+    text_popup_events.write(TextPopupEvent {
+        content: format!("Toast #{}", *count),
+        text_font: TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        location,
+        timeout: TextPopupTimeout::Seconds(4),
+        ..default()
+    });
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupButton, TextPopupEvent, TextPopupInput, TextPopupLocation, TextPopupPlugin,
+    TextPopupSubmitted,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, log_submitted)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Name your character".to_string(),
+        input: Some(TextPopupInput {
+            placeholder: "Letters only".to_string(),
+            max_length: Some(12),
+            filter: Some(|character| character.is_alphabetic()),
+            ..default()
+        }),
+        confirm_button: Some(TextPopupButton {
+            text: "Create".to_string(),
+            ..default()
+        }),
+        dismiss_button: Some(TextPopupButton {
+            text: "Cancel".to_string(),
+            ..default()
+        }),
+        ..default()
+    });
+
+    // Click a field to type into it.
+    text_popup_events.write(TextPopupEvent {
+        content: "Password".to_string(),
+        location: TextPopupLocation::TopLeft,
+        input: Some(TextPopupInput {
+            mask: Some('•'),
+            ..default()
+        }),
+        ..default()
+    });
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Report a bug (Shift+Enter for a new line)".to_string(),
+        location: TextPopupLocation::Bottom,
+        input: Some(TextPopupInput {
+            placeholder: "What happened?".to_string(),
+            max_length: Some(200),
+            multiline: true,
+            show_character_count: true,
+            min_width: Val::Px(400.),
+            ..default()
+        }),
+        ..default()
+    });
+}
+
+fn log_submitted(mut submitted_events: MessageReader<TextPopupSubmitted>) {
+    for submitted in submitted_events.read() {
+        info!("Submitted {:?}", submitted.value);
+    }
+}
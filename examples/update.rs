@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupCommandsExt, TextPopupEvent, TextPopupLocation, TextPopupPlugin, TextPopupTimeout,
+    TextPopupUpdate,
+};
+
+#[derive(Resource)]
+struct Download {
+    popup: Entity,
+    progress: u32,
+    timer: Timer,
+}
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, download)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    let popup = commands
+        .spawn_text_popup(TextPopupEvent {
+            content: "Downloading 0%".to_string(),
+            ..default()
+        })
+        .id();
+    commands.insert_resource(Download {
+        popup,
+        progress: 0,
+        timer: Timer::from_seconds(0.25, TimerMode::Repeating),
+    });
+}
+
+fn download(
+    time: Res<Time>,
+    mut download: ResMut<Download>,
+    mut text_popup_updates: MessageWriter<TextPopupUpdate>,
+) {
+    if download.progress >= 100 || !download.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    download.progress += 10;
+    if download.progress < 100 {
+        text_popup_updates.write(TextPopupUpdate {
+            content: Some(format!("Downloading {}%", download.progress)),
+            ..TextPopupUpdate::new(download.popup)
+        });
+    } else {
+        // The same popup moves out of the way and goes away on its own.
+        text_popup_updates.write(TextPopupUpdate {
+            content: Some("Download complete".to_string()),
+            text_color: Some(Color::linear_rgb(0., 1., 0.).into()),
+            location: Some(TextPopupLocation::BottomRight),
+            timeout: Some(TextPopupTimeout::Seconds(2)),
+            ..TextPopupUpdate::new(download.popup)
+        });
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use bevy_text_popup::{
+    TextPopupButton, TextPopupEvent, TextPopupPage, TextPopupPlugin, TextPopupWizard,
+    TextPopupWizardEnded, TextPopupWizardOutcome,
+};
+
+#[derive(Resource, Default)]
+struct TermsAccepted(bool);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .init_resource::<TermsAccepted>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (accept_terms, log_outcome))
+        .run();
+}
+
+fn terms_accepted(_popup: In<Entity>, terms_accepted: Res<TermsAccepted>) -> bool {
+    terms_accepted.0
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+    let terms_accepted = commands.register_system(terms_accepted);
+
+    text_popup_events.write(TextPopupEvent {
+        wizard: Some(TextPopupWizard {
+            pages: vec![
+                TextPopupPage::new("Welcome aboard!"),
+                TextPopupPage::new("Move with WASD\nJump with Space"),
+                // Next does nothing until the terms are accepted.
+                TextPopupPage::new("Press T to accept the terms").with_validation(terms_accepted),
+                TextPopupPage::new("You're all set, have fun!"),
+            ],
+            ..default()
+        }),
+        dismiss_button: Some(TextPopupButton {
+            text: "Skip".to_string(),
+            ..default()
+        }),
+        modal: Some(Color::linear_rgba(0., 0., 0., 0.75).into()),
+        ..default()
+    });
+}
+
+fn accept_terms(
+    mut terms_accepted: ResMut<TermsAccepted>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyT) && !terms_accepted.0 {
+        terms_accepted.0 = true;
+        info!("Terms accepted");
+    }
+}
+
+fn log_outcome(mut wizard_ended_events: MessageReader<TextPopupWizardEnded>) {
+    for ended in wizard_ended_events.read() {
+        match ended.outcome {
+            TextPopupWizardOutcome::Finished => info!("Onboarding finished"),
+            TextPopupWizardOutcome::Cancelled => {
+                info!("Onboarding skipped on page {}", ended.page + 1)
+            },
+        }
+    }
+}
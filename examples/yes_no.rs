@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use bevy_text_popup::{TextPopupButton, TextPopupButtonPressed, TextPopupEvent, TextPopupPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TextPopupPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, handle_answer)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut text_popup_events: MessageWriter<TextPopupEvent>) {
+    commands.spawn(Camera2d);
+
+    text_popup_events.write(TextPopupEvent {
+        content: "Enable auto-save? (Y/N)".to_string(),
+        confirm_button: Some(TextPopupButton {
+            text: "Yes".to_string(),
+            tag: Some("autosave_yes".to_string()),
+            // Enter still works, since it is listed alongside Y.
+            keys: Some(vec![KeyCode::KeyY, KeyCode::Enter]),
+            ..default()
+        }),
+        dismiss_button: Some(TextPopupButton {
+            text: "No".to_string(),
+            tag: Some("autosave_no".to_string()),
+            keys: Some(vec![KeyCode::KeyN, KeyCode::Escape]),
+            ..default()
+        }),
+        ..default()
+    });
+}
+
+// The buttons keep their default action of closing the popup; the answer is handled here.
+fn handle_answer(mut button_pressed_events: MessageReader<TextPopupButtonPressed>) {
+    for pressed in button_pressed_events.read() {
+        match pressed.tag.as_deref() {
+            Some("autosave_yes") => info!("Auto-save enabled"),
+            Some("autosave_no") => info!("Auto-save disabled"),
+            _ => info!("Button #{} pressed", pressed.button_index),
+        }
+    }
+}
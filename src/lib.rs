@@ -1,13 +1,32 @@
 #![doc = include_str!("../README.md")]
 
 use bevy::{
-    ecs::message::Message,
+    ecs::{message::Message, system::SystemId},
+    input_focus::InputFocus,
+    log::warn,
     prelude::{
-        default, Alpha, App, Color, Commands, Component, Entity, EntityCommands, Name, Node,
-        Plugin, Text, Update,
+        any_with_component, default, Alpha, App, Color, Commands, Component, Entity,
+        EntityCommands, In, IntoScheduleConfigs, KeyCode, Name, Node, Plugin, Resource, Text,
+        Update,
     },
     text::{Justify, TextColor, TextFont, TextLayout},
-    ui::{BackgroundColor, BorderColor, GlobalZIndex, UiRect, Val},
+    time::{Real, Timer, TimerMode, Virtual},
+    ui::{
+        BackgroundColor, BorderColor, FlexDirection, GlobalZIndex, Interaction, Outline, UiRect,
+        Val,
+    },
+};
+use std::{
+    any::TypeId,
+    collections::{BTreeSet, HashSet},
+    fs,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
 };
 
 mod systems;
@@ -19,11 +38,431 @@ pub struct TextPopup;
 #[derive(Debug, Component)]
 pub struct TextPopupNeverExpires;
 
+/// Stops a popup's timeout from counting down while present.
+///
+/// Managed automatically for popups spawned with [`TextPopupEvent::pause_on_hover`].
+#[derive(Debug, Component)]
+pub struct TextPopupPaused;
+
+/// Marks a popup spawned with [`TextPopupEvent::pause_on_hover`].
+#[derive(Debug, Component)]
+pub struct TextPopupPauseOnHover;
+
+/// Spawn counter used to order popups, higher values were spawned later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Component)]
+pub struct TextPopupSpawnOrder(pub u64);
+
+/// Hands out [`TextPopupSpawnOrder`]s, so that every [`App`] numbers its popups on its own.
+#[derive(Debug, Default, Resource)]
+struct TextPopupSpawnCounter(u64);
+
+impl TextPopupSpawnCounter {
+    fn next(&mut self) -> TextPopupSpawnOrder {
+        let spawn_order = TextPopupSpawnOrder(self.0);
+        self.0 += 1;
+        spawn_order
+    }
+}
+
+/// How popups sharing a [`TextPopupLocation`] are laid out.
+///
+/// Popups at the same location are stacked away from the screen edge (downwards for the top and
+/// middle rows, upwards for the bottom row). Custom locations are never stacked.
+#[derive(Debug, Clone, Resource)]
+pub struct TextPopupStacking {
+    pub enabled: bool,
+    /// Gap between stacked popups, in logical pixels.
+    pub spacing: f32,
+    pub order: TextPopupStackOrder,
+}
+
+impl Default for TextPopupStacking {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            spacing: 5.,
+            order: TextPopupStackOrder::OldestFirst,
+        }
+    }
+}
+
+/// Which popup of a stack sits closest to its location's anchor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextPopupStackOrder {
+    NewestFirst,
+    #[default]
+    OldestFirst,
+}
+
+/// Input that dismisses a popup, on top of its buttons and timeout.
+///
+/// Inserted on the popup root unless it is [`TextPopupDismissTrigger::Never`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub enum TextPopupDismissTrigger {
+    #[default]
+    Never,
+    /// A click on the popup itself, outside of its buttons.
+    ClickPopup,
+    /// A click anywhere outside the popup, such as on the modal backdrop.
+    ClickOutside,
+    /// Any keyboard key.
+    ///
+    /// While one of the popup's text fields, choices or buttons has [`InputFocus`], the keys it
+    /// uses are ignored, so typing, picking an option, moving between buttons or pressing a
+    /// bound key never dismisses it.
+    AnyKey,
+    /// Any key, mouse button, gamepad button or touch, with the same exception as
+    /// [`TextPopupDismissTrigger::AnyKey`]. Clicks on the popup's text fields, choices and
+    /// "Don't show this again" checkbox, and the gamepad buttons moving focus, are ignored as
+    /// well.
+    AnyInput,
+}
+
+/// What makes two popups duplicates of each other.
+///
+/// A duplicate arriving while a matching popup is alive restarts that popup's timeout and bumps
+/// its counter, e.g. "Inventory full (x3)", instead of spawning a new popup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextPopupCoalesce {
+    /// Popups with the same content.
+    Content,
+    /// Popups with the same user-supplied key.
+    Key(String),
+}
+
+/// Tracks how many duplicates were merged into a popup sent with [`TextPopupEvent::coalesce`].
+#[derive(Debug, Clone, Component)]
+pub struct TextPopupCoalesced {
+    pub key: String,
+    /// The popup content without the counter.
+    pub content: String,
+    pub count: u32,
+}
+
+/// Marks the queued popup currently on screen.
+#[derive(Debug, Component)]
+pub struct TextPopupQueued;
+
+/// Popups sent with [`TextPopupEvent::queue`] that are waiting to be shown, one at a time.
+#[derive(Debug, Default, Resource)]
+pub struct TextPopupQueue {
+    pub order: TextPopupQueueOrder,
+    pending: Vec<TextPopupEvent>,
+}
+
+impl TextPopupQueue {
+    pub fn new(order: TextPopupQueueOrder) -> Self {
+        Self {
+            order,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, text_popup_event: TextPopupEvent) {
+        self.pending.push(text_popup_event);
+    }
+
+    /// The popup that should be shown next.
+    pub fn peek(&self) -> Option<&TextPopupEvent> {
+        self.pending.get(self.next_index()?)
+    }
+
+    /// Removes the popup that should be shown next.
+    pub fn pop(&mut self) -> Option<TextPopupEvent> {
+        let index = self.next_index()?;
+        Some(self.pending.remove(index))
+    }
+
+    fn next_index(&self) -> Option<usize> {
+        match self.order {
+            TextPopupQueueOrder::Fifo => (!self.pending.is_empty()).then_some(0),
+            // `max_by_key` returns the last maximum, so reverse to keep FIFO among equals.
+            TextPopupQueueOrder::Priority => self
+                .pending
+                .iter()
+                .enumerate()
+                .rev()
+                .max_by_key(|(_, text_popup_event)| text_popup_event.priority)
+                .map(|(index, _)| index),
+        }
+    }
+
+    /// Waiting popups, in the order they were queued.
+    pub fn iter(&self) -> impl Iterator<Item = &TextPopupEvent> {
+        self.pending.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// The order in which [`TextPopupQueue`] shows waiting popups.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextPopupQueueOrder {
+    #[default]
+    Fifo,
+    Priority,
+}
+
+/// Limits how many popups can be on screen at once when new ones are sent as [`TextPopupEvent`]
+/// messages, see [`TextPopupCommandsExt::spawn_text_popup`].
+#[derive(Debug, Default, Resource)]
+pub struct TextPopupCapacity {
+    /// Maximum number of live popups overall.
+    pub global: Option<usize>,
+    /// Maximum number of live popups at specific locations.
+    pub per_location: Vec<(TextPopupLocation, usize)>,
+    pub policy: TextPopupCapacityPolicy,
+    waiting: Vec<TextPopupEvent>,
+}
+
+impl TextPopupCapacity {
+    pub fn new(policy: TextPopupCapacityPolicy) -> Self {
+        Self {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_global_limit(mut self, limit: usize) -> Self {
+        self.global = Some(limit);
+        self
+    }
+
+    pub fn with_location_limit(mut self, location: TextPopupLocation, limit: usize) -> Self {
+        self.per_location.push((location, limit));
+        self
+    }
+
+    pub fn location_limit(&self, location: TextPopupLocation) -> Option<usize> {
+        self.per_location
+            .iter()
+            .find(|(limited_location, _)| *limited_location == location)
+            .map(|(_, limit)| *limit)
+    }
+
+    /// Popups held back by [`TextPopupCapacityPolicy::Queue`], oldest first.
+    pub fn waiting(&self) -> impl Iterator<Item = &TextPopupEvent> {
+        self.waiting.iter()
+    }
+
+    pub fn clear_waiting(&mut self) {
+        self.waiting.clear();
+    }
+}
+
+/// Popup keys the player asked not to see again through the checkbox of popups sent with a
+/// [`TextPopupEvent::suppress_key`].
+///
+/// With a path set, the keys are loaded from that file when the resource is created and saved
+/// to it whenever they change, one key per line with backslashes and line breaks escaped.
+#[derive(Debug, Resource)]
+pub struct TextPopupSuppression {
+    keys: BTreeSet<String>,
+    path: Option<PathBuf>,
+    /// Text of the checkbox shown on suppressible popups.
+    pub label: String,
+}
+
+impl Default for TextPopupSuppression {
+    fn default() -> Self {
+        Self {
+            keys: BTreeSet::new(),
+            path: None,
+            label: "Don't show this again".to_string(),
+        }
+    }
+}
+
+impl TextPopupSuppression {
+    /// Persists the keys to `path`, loading any keys already saved there.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        if let Err(error) = self.load() {
+            warn!("Failed to load suppressed text popups: {}", error);
+        }
+        self
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn is_suppressed(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.keys.iter().map(String::as_str)
+    }
+
+    pub fn suppress(&mut self, key: impl Into<String>) {
+        if self.keys.insert(key.into()) {
+            self.save_or_warn();
+        }
+    }
+
+    pub fn unsuppress(&mut self, key: &str) {
+        if self.keys.remove(key) {
+            self.save_or_warn();
+        }
+    }
+
+    /// Shows every suppressed popup again, e.g. from a settings menu.
+    pub fn reset(&mut self) {
+        self.keys.clear();
+        self.save_or_warn();
+    }
+
+    /// Replaces the keys with the ones saved at the path. A missing file counts as empty.
+    pub fn load(&mut self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        self.keys = match fs::read_to_string(path) {
+            Ok(contents) => contents
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(unescape_suppression_key)
+                .collect(),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(error) => return Err(error),
+        };
+        Ok(())
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for key in &self.keys {
+            contents.push_str(&escape_suppression_key(key));
+            contents.push('\n');
+        }
+        fs::write(path, contents)
+    }
+
+    fn save_or_warn(&self) {
+        if let Err(error) = self.save() {
+            warn!("Failed to save suppressed text popups: {}", error);
+        }
+    }
+
+    fn is_suppressed_event(&self, text_popup_event: &TextPopupEvent) -> bool {
+        text_popup_event
+            .suppress_key
+            .as_deref()
+            .is_some_and(|key| self.is_suppressed(key))
+    }
+}
+
+/// Escapes the characters that would otherwise split a key over several lines of the save file.
+fn escape_suppression_key(key: &str) -> String {
+    key.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape_suppression_key(line: &str) -> String {
+    let mut key = String::with_capacity(line.len());
+    let mut characters = line.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            key.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n') => key.push('\n'),
+            Some('r') => key.push('\r'),
+            Some(escaped) => key.push(escaped),
+            None => key.push('\\'),
+        }
+    }
+    key
+}
+
+/// The "Don't show this again" checkbox of a popup.
+#[derive(Debug, Component)]
+pub struct TextPopupSuppressCheckbox {
+    pub root_id: Entity,
+    pub key: String,
+    pub text: Entity,
+    pub checked: bool,
+}
+
+/// What happens to a new popup when its location (or the screen) is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextPopupCapacityPolicy {
+    /// Close the oldest live popup to make room.
+    #[default]
+    DropOldest,
+    /// Discard the new popup.
+    DropNewest,
+    /// Hold the new popup back until there is room for it.
+    Queue,
+}
+
+/// The entities making up a popup, stored on its root entity.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct TextPopupNodes {
+    /// The bordered node holding the text and the action row.
+    pub panel: Entity,
+    /// The entity holding the popup's [`Text`].
+    pub text: Entity,
+    /// The row holding the popup's buttons.
+    pub actions: Entity,
+}
+
+/// Counts down a time-based popup timeout.
+///
+/// The timer is ticked by the clock chosen with [`TextPopupEvent::clock`] and the popup is closed
+/// once it finishes. Query for this component to inspect or change a popup's timeout at runtime.
 #[derive(Debug, Default, Component)]
 pub struct TextPopupExpires {
-    pub expiration_time: f64,
+    pub timer: Timer,
+}
+
+impl TextPopupExpires {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            timer: Timer::new(duration, TimerMode::Once),
+        }
+    }
+
+    /// Time left before the popup expires.
+    pub fn remaining(&self) -> Duration {
+        self.timer.remaining()
+    }
+
+    /// Pushes the expiration back by `duration`.
+    pub fn extend(&mut self, duration: Duration) {
+        let extended = self.timer.duration() + duration;
+        self.timer.set_duration(extended);
+    }
+
+    /// Restarts the countdown from the full duration.
+    pub fn reset(&mut self) {
+        self.timer.reset();
+    }
 }
 
+/// The `T` of the `Time<T>` clock that measures a popup's [`TextPopupExpires`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct TextPopupUsesClock(pub TypeId);
+
 #[derive(Debug, Default, Component)]
 pub struct TextPopupExpiresInFrames {
     pub frames_remaining: u32,
@@ -50,7 +489,7 @@ pub struct TextPopupTextNode {
 pub struct TextPopupActionNode(pub Node);
 
 /// Users send these events to create text popups.
-#[derive(Debug, Message)]
+#[derive(Debug, Clone, Message)]
 pub struct TextPopupEvent {
     pub content: String,
     pub text_font: TextFont,
@@ -62,11 +501,44 @@ pub struct TextPopupEvent {
     pub modal: Option<BackgroundColor>,
     pub text_alignment: Justify,
     pub background_color: BackgroundColor,
+    /// Shown first in the action row, closing the popup with [`TextPopupCloseReason::Confirmed`].
     pub confirm_button: Option<TextPopupButton>,
+    /// Shown last in the action row, closing the popup with [`TextPopupCloseReason::Dismissed`].
     pub dismiss_button: Option<TextPopupButton>,
+    /// Extra buttons shown in order between the confirm and dismiss buttons.
+    pub buttons: Vec<TextPopupButton>,
+    /// Shown in the top-right corner, closing the popup with [`TextPopupCloseReason::Closed`].
+    pub close_button: Option<TextPopupCloseButton>,
+    /// An editable text field shown under the content, sent back in [`TextPopupSubmitted`].
+    pub input: Option<TextPopupInput>,
+    /// A list of options shown under the content, sent back in [`TextPopupSelected`].
+    pub choices: Option<TextPopupChoices>,
+    /// Shows the content as several pages with Back, Next and Finish buttons.
+    pub wizard: Option<TextPopupWizard>,
+    /// Layout direction of the action row, use the reverse directions to flip the button order.
+    pub action_direction: FlexDirection,
+    /// Position in the action row of the button given [`InputFocus`] when the popup spawns.
+    pub default_focus: Option<usize>,
     pub location: TextPopupLocation,
     pub z_index: GlobalZIndex,
     pub timeout: TextPopupTimeout,
+    /// The clock that counts down [`TextPopupTimeout::Seconds`] and [`TextPopupTimeout::Duration`].
+    pub clock: TextPopupClock,
+    /// Stops the timeout from counting down while the pointer is over the popup or its buttons.
+    pub pause_on_hover: bool,
+    /// Input that dismisses the popup with [`TextPopupCloseReason::Dismissed`].
+    pub dismiss_on: TextPopupDismissTrigger,
+    /// Merges this popup into a live popup with the same key instead of spawning a new one.
+    pub coalesce: Option<TextPopupCoalesce>,
+    /// Waits in the [`TextPopupQueue`] until no other queued popup is showing, see
+    /// [`TextPopupCommandsExt::spawn_text_popup`].
+    pub queue: bool,
+    /// Queued popups with a higher priority are shown first when using
+    /// [`TextPopupQueueOrder::Priority`].
+    pub priority: i32,
+    /// Shows a "Don't show this again" checkbox. Once ticked, popups sent with the same key are
+    /// dropped, see [`TextPopupSuppression`] and [`TextPopupCommandsExt::spawn_text_popup`].
+    pub suppress_key: Option<String>,
     pub name: Option<Name>,
     /// Optional function to add custom components to the popup entity.
     /// The function receives mutable access to the EntityCommands for the root popup entity.
@@ -91,16 +563,85 @@ impl Default for TextPopupEvent {
             background_color: BackgroundColor::from(Color::BLACK),
             confirm_button: default(),
             dismiss_button: default(),
+            close_button: None,
+            input: None,
+            choices: None,
+            wizard: None,
+            buttons: default(),
+            action_direction: FlexDirection::Row,
+            default_focus: None,
             location: default(),
             z_index: GlobalZIndex(i32::MAX),
             timeout: TextPopupTimeout::Never,
+            clock: default(),
+            pause_on_hover: false,
+            dismiss_on: default(),
+            coalesce: None,
+            queue: false,
+            priority: 0,
+            suppress_key: None,
             name: None,
             custom_component: None,
         }
     }
 }
 
-#[derive(Debug, Default)]
+/// Users send these events to change a live text popup in place.
+///
+/// Only the fields set to `Some` are changed.
+#[derive(Debug, Clone, Message)]
+pub struct TextPopupUpdate {
+    pub target: TextPopupTarget,
+    pub content: Option<String>,
+    pub text_color: Option<TextColor>,
+    pub background_color: Option<BackgroundColor>,
+    pub border: Option<UiRect>,
+    pub border_color: Option<BorderColor>,
+    pub location: Option<TextPopupLocation>,
+    /// Restarts the popup's timeout, replacing the previous one.
+    pub timeout: Option<TextPopupTimeout>,
+    /// The clock used for a new second-based `timeout`.
+    pub clock: TextPopupClock,
+}
+
+impl TextPopupUpdate {
+    pub fn new(target: impl Into<TextPopupTarget>) -> Self {
+        Self {
+            target: target.into(),
+            content: None,
+            text_color: None,
+            background_color: None,
+            border: None,
+            border_color: None,
+            location: None,
+            timeout: None,
+            clock: default(),
+        }
+    }
+}
+
+/// Selects the popups changed by a [`TextPopupUpdate`].
+#[derive(Debug, Clone)]
+pub enum TextPopupTarget {
+    /// The popup with this root entity.
+    Entity(Entity),
+    /// Every popup spawned with this [`TextPopupEvent::name`].
+    Name(Name),
+}
+
+impl From<Entity> for TextPopupTarget {
+    fn from(entity: Entity) -> Self {
+        Self::Entity(entity)
+    }
+}
+
+impl From<Name> for TextPopupTarget {
+    fn from(name: Name) -> Self {
+        Self::Name(name)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
 pub enum TextPopupLocation {
     TopLeft,
     Top,
@@ -116,13 +657,44 @@ pub enum TextPopupLocation {
     Custom(f32, f32),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum TextPopupTimeout {
     Never,
     Seconds(u32),
+    /// Like [`TextPopupTimeout::Seconds`], but allows sub-second precision.
+    Duration(Duration),
     Frames(u32),
 }
 
+/// The clock that drives second-based popup timeouts.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum TextPopupClock {
+    /// `Time<Virtual>`: stops counting while virtual time is paused.
+    #[default]
+    Virtual,
+    /// `Time<Real>`: keeps counting while virtual time is paused.
+    Real,
+    /// A user-defined `Time<T>` clock, created with [`TextPopupClock::custom`].
+    Custom(fn(&mut EntityCommands, Duration)),
+}
+
+impl TextPopupClock {
+    /// Uses the `Time<T>` resource as the popup clock.
+    ///
+    /// The clock has to be registered with [`TextPopupAppExt::add_text_popup_clock`].
+    pub fn custom<T: Default + Send + Sync + 'static>() -> Self {
+        Self::Custom(text_popup::insert_expiration::<T>)
+    }
+
+    fn insert_expiration(self, entity_commands: &mut EntityCommands, duration: Duration) {
+        match self {
+            Self::Virtual => text_popup::insert_expiration::<Virtual>(entity_commands, duration),
+            Self::Real => text_popup::insert_expiration::<Real>(entity_commands, duration),
+            Self::Custom(insert_expiration) => insert_expiration(entity_commands, duration),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TextPopupButton {
     pub text: String,
@@ -133,7 +705,23 @@ pub struct TextPopupButton {
     pub padding: UiRect,
     pub margin: UiRect,
     pub background_color: BackgroundColor,
-    pub action: fn(&mut Commands, Entity),
+    pub action: TextPopupAction,
+    /// Sent back in [`TextPopupButtonPressed`] to tell buttons apart.
+    pub tag: Option<String>,
+    /// Keys that activate the button when its popup is the topmost one. `None` binds Enter to
+    /// the confirm button, Escape to the dismiss button and nothing to the others.
+    pub keys: Option<Vec<KeyCode>>,
+    /// Whether the button can be pressed. Disabled buttons use the `disabled` style, ignore
+    /// presses and are skipped by focus navigation.
+    pub enabled: TextPopupButtonEnabled,
+    /// Overrides applied while the cursor is over the button.
+    pub hovered: TextPopupButtonStyle,
+    /// Overrides applied while the button is held down.
+    pub pressed: TextPopupButtonStyle,
+    /// Overrides applied while the button has [`InteractionDisabled`](bevy::ui::InteractionDisabled).
+    pub disabled: TextPopupButtonStyle,
+    /// Focus ring drawn around the button while it has [`InputFocus`].
+    pub focus_outline: Outline,
 }
 
 impl Default for TextPopupButton {
@@ -150,7 +738,587 @@ impl Default for TextPopupButton {
             padding: UiRect::all(Val::Px(0.0)),
             margin: UiRect::all(Val::Px(5.0)),
             background_color: BackgroundColor::from(Color::BLACK),
-            action: |commands, entity| commands.entity(entity).despawn(),
+            action: default(),
+            tag: None,
+            keys: None,
+            enabled: default(),
+            hovered: TextPopupButtonStyle {
+                scale: Some(1.05),
+                ..default()
+            },
+            pressed: TextPopupButtonStyle {
+                scale: Some(0.95),
+                ..default()
+            },
+            disabled: TextPopupButtonStyle {
+                text_color: Some(TextColor::from(Color::srgb(0.5, 0.5, 0.5))),
+                ..default()
+            },
+            focus_outline: Outline::new(Val::Px(2.), Val::Px(2.), Color::WHITE),
+        }
+    }
+}
+
+/// A single-line text field inside a popup, e.g. for naming a save slot.
+///
+/// The field takes [`InputFocus`] when the popup spawns. Enter or the confirm button submits
+/// its value in a [`TextPopupSubmitted`] message. Enter does nothing while the confirm button
+/// is disabled.
+#[derive(Debug, Clone)]
+pub struct TextPopupInput {
+    pub initial_value: String,
+    /// Shown while the field is empty.
+    pub placeholder: String,
+    /// Maximum number of characters.
+    pub max_length: Option<usize>,
+    /// Typed characters are only accepted if this returns `true`.
+    pub filter: Option<fn(char) -> bool>,
+    /// Drawn at the insertion point while the field has focus.
+    pub caret: char,
+    /// Shown in place of every character, e.g. `'•'` for passwords. The submitted value is
+    /// still the real text.
+    pub mask: Option<char>,
+    /// Turns the field into a text area that wraps at `min_width`. Shift+Enter inserts a new
+    /// line, Enter still submits. Single-line fields submit on Shift+Enter as well.
+    pub multiline: bool,
+    /// Shows the number of characters typed, out of `max_length` if set, under the field.
+    pub show_character_count: bool,
+    /// Defaults to the popup's `text_font`.
+    pub text_font: Option<TextFont>,
+    /// Defaults to the popup's `text_color`.
+    pub text_color: Option<TextColor>,
+    pub placeholder_color: TextColor,
+    pub background_color: BackgroundColor,
+    pub border: UiRect,
+    pub border_color: BorderColor,
+    pub padding: UiRect,
+    pub margin: UiRect,
+    pub min_width: Val,
+}
+
+impl Default for TextPopupInput {
+    fn default() -> Self {
+        Self {
+            initial_value: String::new(),
+            placeholder: String::new(),
+            max_length: None,
+            filter: None,
+            caret: '|',
+            mask: None,
+            multiline: false,
+            show_character_count: false,
+            text_font: None,
+            text_color: None,
+            placeholder_color: TextColor::from(Color::srgb(0.5, 0.5, 0.5)),
+            background_color: BackgroundColor::from(Color::srgb(0.1, 0.1, 0.1)),
+            border: UiRect::all(Val::Px(1.0)),
+            border_color: BorderColor::from(Color::WHITE),
+            padding: UiRect::all(Val::Px(4.0)),
+            margin: UiRect::all(Val::Px(5.0)),
+            min_width: Val::Px(200.0),
+        }
+    }
+}
+
+/// The value of a popup's text field, kept on the popup root.
+#[derive(Debug, Clone, Component)]
+pub struct TextPopupInputState {
+    value: String,
+    /// Insertion point, in characters.
+    caret: usize,
+    field: Entity,
+    character_count: Option<Entity>,
+    text_color: TextColor,
+    input: TextPopupInput,
+}
+
+impl TextPopupInputState {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Replaces the value and moves the caret to its end.
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.caret = self.value.chars().count();
+    }
+
+    /// The field node showing the value.
+    pub fn field(&self) -> Entity {
+        self.field
+    }
+
+    fn caret_byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.caret)
+            .map_or(self.value.len(), |(index, _)| index)
+    }
+
+    fn insert(&mut self, character: char) {
+        let full = self
+            .input
+            .max_length
+            .is_some_and(|max_length| self.value.chars().count() >= max_length);
+        let allowed = if character == '\n' {
+            self.input.multiline
+        } else {
+            !character.is_control() && self.input.filter.is_none_or(|filter| filter(character))
+        };
+        if full || !allowed {
+            return;
+        }
+        let index = self.caret_byte_index();
+        self.value.insert(index, character);
+        self.caret += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.caret > 0 {
+            self.caret -= 1;
+            let index = self.caret_byte_index();
+            self.value.remove(index);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.caret < self.value.chars().count() {
+            let index = self.caret_byte_index();
+            self.value.remove(index);
+        }
+    }
+
+    fn move_caret(&mut self, caret: usize) {
+        self.caret = caret.min(self.value.chars().count());
+    }
+
+    /// The text to draw in the field, and whether it is the placeholder.
+    fn display(&self, focused: bool) -> (String, bool) {
+        let caret = if focused {
+            self.input.caret.to_string()
+        } else {
+            String::new()
+        };
+        if self.value.is_empty() {
+            return (caret + &self.input.placeholder, true);
+        }
+        let mut shown: String = match self.input.mask {
+            Some(mask) => self
+                .value
+                .chars()
+                .map(|character| if character == '\n' { character } else { mask })
+                .collect(),
+            None => self.value.clone(),
+        };
+        let index = shown
+            .char_indices()
+            .nth(self.caret)
+            .map_or(shown.len(), |(index, _)| index);
+        shown.insert_str(index, &caret);
+        (shown, false)
+    }
+
+    fn character_count(&self) -> String {
+        let count = self.value.chars().count();
+        match self.input.max_length {
+            Some(max_length) => format!("{}/{}", count, max_length),
+            None => count.to_string(),
+        }
+    }
+}
+
+/// Marks the text field node of the popup `root_id`.
+#[derive(Debug, Component)]
+pub struct TextPopupInputField {
+    pub root_id: Entity,
+}
+
+/// Sent when a popup's text field is submitted with Enter or the confirm button.
+#[derive(Debug, Clone, Message)]
+pub struct TextPopupSubmitted {
+    pub popup: Entity,
+    pub value: String,
+}
+
+/// A vertical list of options the player picks from, e.g. a difficulty.
+///
+/// Options are toggled by clicking them or by focusing them and pressing Enter or South. Pressing
+/// the confirm button sends the selected options in a [`TextPopupSelected`] message, while other
+/// buttons, [`TextPopupEvent::dismiss_on`], the close button or a timeout send none.
+#[derive(Debug, Clone)]
+pub struct TextPopupChoices {
+    pub options: Vec<String>,
+    pub selection: TextPopupSelection,
+    /// Options selected when the popup spawns.
+    pub selected: Vec<usize>,
+    /// Defaults to the popup's `text_font`.
+    pub text_font: Option<TextFont>,
+    /// Defaults to the popup's `text_color`.
+    pub text_color: Option<TextColor>,
+    pub padding: UiRect,
+    pub margin: UiRect,
+    /// Overrides applied while the cursor is over an option.
+    pub hovered: TextPopupButtonStyle,
+    /// Focus ring drawn around an option while it has [`InputFocus`].
+    pub focus_outline: Outline,
+}
+
+impl Default for TextPopupChoices {
+    fn default() -> Self {
+        Self {
+            options: Vec::new(),
+            selection: default(),
+            selected: Vec::new(),
+            text_font: None,
+            text_color: None,
+            padding: UiRect::horizontal(Val::Px(5.0)),
+            margin: UiRect::all(Val::Px(5.0)),
+            hovered: TextPopupButtonStyle {
+                background_color: Some(BackgroundColor::from(Color::srgba(1.0, 1.0, 1.0, 0.1))),
+                ..default()
+            },
+            focus_outline: Outline::new(Val::Px(2.), Val::Px(2.), Color::WHITE),
+        }
+    }
+}
+
+/// How many [`TextPopupChoices`] options can be selected at once.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextPopupSelection {
+    /// Radio buttons, selecting an option deselects the others.
+    #[default]
+    Single,
+    /// Checkboxes, each option is toggled on its own.
+    Multiple,
+}
+
+/// The selected options of a popup's choice list, kept on the popup root.
+#[derive(Debug, Clone, Component)]
+pub struct TextPopupChoiceState {
+    selected: Vec<usize>,
+    /// Text entity of each option.
+    texts: Vec<Entity>,
+    choices: TextPopupChoices,
+}
+
+impl TextPopupChoiceState {
+    /// Indices of the selected options, in ascending order.
+    pub fn selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    fn toggle(&mut self, index: usize) {
+        match self.choices.selection {
+            TextPopupSelection::Single => self.selected = vec![index],
+            TextPopupSelection::Multiple => match self.selected.binary_search(&index) {
+                Ok(position) => {
+                    self.selected.remove(position);
+                },
+                Err(position) => self.selected.insert(position, index),
+            },
+        }
+    }
+
+    /// The option text with a radio or checkbox marker in front.
+    fn label(&self, index: usize) -> String {
+        let selected = self.selected.contains(&index);
+        let marker = match (self.choices.selection, selected) {
+            (TextPopupSelection::Single, true) => "(•)",
+            (TextPopupSelection::Single, false) => "( )",
+            (TextPopupSelection::Multiple, true) => "[x]",
+            (TextPopupSelection::Multiple, false) => "[ ]",
+        };
+        format!("{} {}", marker, self.choices.options[index])
+    }
+}
+
+/// Marks an option of the choice list of the popup `root_id`.
+#[derive(Debug, Component)]
+pub struct TextPopupChoice {
+    pub root_id: Entity,
+    pub index: usize,
+}
+
+/// Sent when a popup with a choice list is confirmed.
+#[derive(Debug, Clone, Message)]
+pub struct TextPopupSelected {
+    pub popup: Entity,
+    /// Indices of the selected options, in ascending order.
+    pub selected: Vec<usize>,
+    /// Index of the confirm button in the action row, or `None` when the popup's text field was
+    /// submitted without a confirm button.
+    pub button_index: Option<usize>,
+}
+
+/// Several pages shown one after another in the same popup, e.g. an onboarding tour.
+///
+/// The pages replace the popup's `content`, and the Back, Next and Finish buttons replace its
+/// `confirm_button` and `buttons`. Finish closes the popup with
+/// [`TextPopupCloseReason::Confirmed`], and a [`TextPopupWizardEnded`] message is sent however
+/// the popup goes away.
+#[derive(Debug, Clone)]
+pub struct TextPopupWizard {
+    /// Needs at least one page. A popup with an empty wizard is shown as a plain popup with its
+    /// `content` instead, with a warning.
+    pub pages: Vec<TextPopupPage>,
+    /// The actions of the navigation buttons are replaced by the page navigation.
+    pub back_button: TextPopupButton,
+    pub next_button: TextPopupButton,
+    pub finish_button: TextPopupButton,
+    /// Shows "Page 2 of 5" below the page content.
+    pub page_indicator: bool,
+}
+
+impl Default for TextPopupWizard {
+    fn default() -> Self {
+        Self {
+            pages: Vec::new(),
+            back_button: TextPopupButton {
+                text: "Back".to_string(),
+                ..default()
+            },
+            next_button: TextPopupButton {
+                text: "Next".to_string(),
+                keys: Some(vec![KeyCode::Enter]),
+                ..default()
+            },
+            finish_button: TextPopupButton {
+                text: "Finish".to_string(),
+                ..default()
+            },
+            page_indicator: true,
+        }
+    }
+}
+
+/// One page of a [`TextPopupWizard`].
+#[derive(Debug, Clone, Default)]
+pub struct TextPopupPage {
+    pub content: String,
+    /// Checked before moving past this page with Next or Finish, which does nothing while the
+    /// system returns `false`. The system receives the popup root entity.
+    pub validate: Option<SystemId<In<Entity>, bool>>,
+}
+
+impl TextPopupPage {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            validate: None,
+        }
+    }
+
+    pub fn with_validation(mut self, validate: SystemId<In<Entity>, bool>) -> Self {
+        self.validate = Some(validate);
+        self
+    }
+}
+
+/// The current page of a wizard popup, stored on its root entity.
+#[derive(Debug, Clone, Component)]
+pub struct TextPopupWizardState {
+    page: usize,
+    pages: Vec<TextPopupPage>,
+    back: Entity,
+    next: Entity,
+    finish: Entity,
+    indicator: Option<Entity>,
+}
+
+impl TextPopupWizardState {
+    /// Index of the page being shown.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_last_page(&self) -> bool {
+        self.page + 1 >= self.pages.len()
+    }
+
+    /// Moves to the given page without validating the current one.
+    pub fn set_page(&mut self, page: usize) {
+        self.page = page.min(self.pages.len().saturating_sub(1));
+    }
+
+    fn indicator_text(&self) -> String {
+        format!("Page {} of {}", self.page + 1, self.pages.len())
+    }
+}
+
+/// How a wizard popup ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextPopupWizardOutcome {
+    /// The Finish button was pressed on the last page.
+    Finished,
+    /// The popup closed any other way, e.g. its dismiss button or a timeout.
+    Cancelled,
+}
+
+/// Sent when a popup with a [`TextPopupWizard`] closes.
+#[derive(Debug, Clone, Message)]
+pub struct TextPopupWizardEnded {
+    pub popup: Entity,
+    pub outcome: TextPopupWizardOutcome,
+    /// The page that was showing when the popup closed.
+    pub page: usize,
+}
+
+/// When a [`TextPopupButton`] can be pressed.
+///
+/// Inserted on the spawned button unless it is [`TextPopupButtonEnabled::Always`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub enum TextPopupButtonEnabled {
+    #[default]
+    Always,
+    Never,
+    /// Evaluated every frame, enabling the button while the system returns `true`.
+    /// Register the system with `commands.register_system`.
+    When(SystemId<(), bool>),
+}
+
+/// A small button in the top-right corner of a popup that closes it.
+#[derive(Debug, Clone)]
+pub struct TextPopupCloseButton {
+    pub text: String,
+    pub text_font: TextFont,
+    pub text_color: TextColor,
+    pub border: UiRect,
+    pub border_color: BorderColor,
+    pub padding: UiRect,
+    pub background_color: BackgroundColor,
+    /// Distance from the top-right corner of the popup.
+    pub offset: Val,
+    pub hovered: TextPopupButtonStyle,
+    pub pressed: TextPopupButtonStyle,
+}
+
+impl Default for TextPopupCloseButton {
+    fn default() -> Self {
+        Self {
+            text: "X".to_string(),
+            text_font: TextFont {
+                font_size: 16.0,
+                ..Default::default()
+            },
+            text_color: TextColor::from(Color::WHITE),
+            border: UiRect::all(Val::Px(0.0)),
+            border_color: BorderColor::from(Color::WHITE),
+            padding: UiRect::horizontal(Val::Px(4.0)),
+            background_color: BackgroundColor::from(Color::NONE),
+            offset: Val::Px(2.0),
+            hovered: TextPopupButtonStyle {
+                text_color: Some(TextColor::from(Color::srgb(1.0, 0.3, 0.3))),
+                ..default()
+            },
+            pressed: TextPopupButtonStyle {
+                scale: Some(0.9),
+                ..default()
+            },
+        }
+    }
+}
+
+/// Marks the corner close button of the popup `root_id`.
+#[derive(Debug, Component)]
+pub struct TextPopupCloseButtonNode {
+    pub root_id: Entity,
+}
+
+/// Style overrides for one interaction state of a [`TextPopupButton`].
+/// Fields left as `None` keep the button's normal look.
+#[derive(Debug, Clone, Default)]
+pub struct TextPopupButtonStyle {
+    pub background_color: Option<BackgroundColor>,
+    pub border_color: Option<BorderColor>,
+    pub text_color: Option<TextColor>,
+    /// Uniform scale of the button, where `1.0` is its normal size.
+    pub scale: Option<f32>,
+}
+
+impl TextPopupButtonStyle {
+    fn or(&self, base: &Self) -> Self {
+        Self {
+            background_color: self.background_color.or(base.background_color),
+            border_color: self.border_color.or(base.border_color),
+            text_color: self.text_color.or(base.text_color),
+            scale: self.scale.or(base.scale),
+        }
+    }
+}
+
+/// The styles of a spawned popup button, applied by its current [`Interaction`].
+#[derive(Component, Debug, Clone)]
+pub struct TextPopupButtonStyles {
+    pub text: Entity,
+    pub normal: TextPopupButtonStyle,
+    pub hovered: TextPopupButtonStyle,
+    pub pressed: TextPopupButtonStyle,
+    pub disabled: TextPopupButtonStyle,
+    pub focus_outline: Outline,
+}
+
+impl TextPopupButtonStyles {
+    /// The resolved style for a button state, falling back to the normal style.
+    pub fn resolve(&self, interaction: Interaction, disabled: bool) -> TextPopupButtonStyle {
+        let overrides = match (disabled, interaction) {
+            (true, _) => &self.disabled,
+            (false, Interaction::Pressed) => &self.pressed,
+            (false, Interaction::Hovered) => &self.hovered,
+            (false, Interaction::None) => return self.normal.clone(),
+        };
+        overrides.or(&self.normal)
+    }
+}
+
+/// A button action closure, called with the popup's root entity.
+pub type TextPopupActionFn = dyn Fn(&mut Commands, Entity) + Send + Sync;
+
+/// What a popup button does when pressed.
+#[derive(Clone)]
+pub enum TextPopupAction {
+    /// A closure called with the popup's root entity.
+    Closure(Arc<TextPopupActionFn>),
+    /// A registered one-shot system, run with the popup's root entity as input.
+    System(SystemId<In<Entity>>),
+}
+
+impl TextPopupAction {
+    pub fn new(action: impl Fn(&mut Commands, Entity) + Send + Sync + 'static) -> Self {
+        Self::Closure(Arc::new(action))
+    }
+
+    /// Runs a system registered with [`Commands::register_system`] or
+    /// [`World::register_system`](bevy::prelude::World::register_system).
+    pub fn system(system_id: SystemId<In<Entity>>) -> Self {
+        Self::System(system_id)
+    }
+
+    pub fn run(&self, commands: &mut Commands, root_id: Entity) {
+        match self {
+            Self::Closure(action) => action(commands, root_id),
+            Self::System(system_id) => {
+                commands.run_system_with(*system_id, root_id);
+            },
+        }
+    }
+}
+
+/// Closes the popup.
+impl Default for TextPopupAction {
+    fn default() -> Self {
+        Self::new(|commands, entity| commands.entity(entity).despawn())
+    }
+}
+
+impl std::fmt::Debug for TextPopupAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closure(_) => f.write_str("Closure"),
+            Self::System(system_id) => f.debug_tuple("System").field(system_id).finish(),
         }
     }
 }
@@ -158,21 +1326,485 @@ impl Default for TextPopupButton {
 #[derive(Debug, Component)]
 pub struct TextPopupButtonActionData {
     pub root_id: Entity,
-    pub action: fn(&mut Commands, Entity),
+    /// Position of the button in the popup's action row.
+    pub index: usize,
+    pub action: TextPopupAction,
+    pub tag: Option<String>,
+    pub keys: Vec<KeyCode>,
+    /// Reported in [`TextPopupClosed`] if the action closes the popup.
+    pub close_reason: TextPopupCloseReason,
+}
+
+/// Why a text popup was closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextPopupCloseReason {
+    /// A [`TextPopupTimeout::Seconds`] or [`TextPopupTimeout::Duration`] timeout elapsed.
+    TimedOut,
+    /// A [`TextPopupTimeout::Frames`] timeout elapsed.
+    FrameExpired,
+    /// The confirm button was pressed.
+    Confirmed,
+    /// The dismiss button was pressed, or the [`TextPopupEvent::dismiss_on`] trigger fired.
+    Dismissed,
+    /// One of [`TextPopupEvent::buttons`] was pressed, given by its position in the action row.
+    Button(usize),
+    /// The [`TextPopupEvent::close_button`] was pressed.
+    Closed,
+    /// The popup was closed to make room under [`TextPopupCapacityPolicy::DropOldest`].
+    Evicted,
+    /// The popup root entity was despawned by other code.
+    Despawned,
+}
+
+/// Inserted on a popup root right before the plugin despawns it, so that
+/// [`TextPopupClosed`] can report why it went away.
+#[derive(Debug, Component)]
+pub struct TextPopupClosing(pub TextPopupCloseReason);
+
+/// Sent once a text popup has been spawned.
+#[derive(Debug, Clone, Message)]
+pub struct TextPopupSpawned {
+    pub entity: Entity,
+    pub name: Option<Name>,
+}
+
+/// Sent once a text popup has been closed.
+#[derive(Debug, Clone, Message)]
+pub struct TextPopupClosed {
+    pub entity: Entity,
+    pub name: Option<Name>,
+    pub reason: TextPopupCloseReason,
+}
+
+/// Sent whenever a popup button is activated, before its action runs.
+#[derive(Debug, Clone, Message)]
+pub struct TextPopupButtonPressed {
+    pub popup: Entity,
+    /// Position of the button in the popup's action row.
+    pub button_index: usize,
+    /// The [`TextPopupButton::tag`] of the button.
+    pub tag: Option<String>,
+}
+
+/// How a prompt popup was answered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextPopupPromptResult {
+    /// A button closed the popup, given by its position in the action row and its tag.
+    Button { index: usize, tag: Option<String> },
+    /// The popup was closed without a button, e.g. by its close button or dismiss trigger.
+    Dismissed,
+    /// The popup's timeout elapsed.
+    TimedOut,
+}
+
+#[derive(Debug, Default)]
+struct TextPopupPromptState {
+    result: Option<TextPopupPromptResult>,
+    /// One waker per task awaiting a clone of the handle.
+    wakers: Vec<Waker>,
+}
+
+/// A handle to the answer of a popup spawned with [`TextPopupCommandsExt::prompt_text_popup`].
+///
+/// The handle resolves once the popup closes. Poll it with [`TextPopupPrompt::result`] from a
+/// system, or `.await` it from an async task. Clones share the answer, so several tasks can
+/// await the same prompt.
+#[derive(Debug, Clone, Component)]
+pub struct TextPopupPrompt {
+    popup: Entity,
+    state: Arc<Mutex<TextPopupPromptState>>,
+}
+
+impl TextPopupPrompt {
+    /// The root entity of the prompt popup.
+    pub fn popup(&self) -> Entity {
+        self.popup
+    }
+
+    /// The answer, or `None` while the popup is still open.
+    pub fn result(&self) -> Option<TextPopupPromptResult> {
+        self.state.lock().unwrap().result.clone()
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.state.lock().unwrap().result.is_some()
+    }
+
+    fn resolve(&self, result: TextPopupPromptResult) {
+        let mut state = self.state.lock().unwrap();
+        state.result = Some(result);
+        for waker in state.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for TextPopupPrompt {
+    type Output = TextPopupPromptResult;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match &state.result {
+            Some(result) => Poll::Ready(result.clone()),
+            None => {
+                if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                    state.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            },
+        }
+    }
+}
+
+/// Kept on a prompt popup root to resolve its [`TextPopupPrompt`] when the popup closes.
+#[derive(Debug, Component)]
+struct TextPopupPromptResolver(TextPopupPrompt);
+
+/// Inserted on a popup root while a button's action runs, so a prompt closed by that action
+/// resolves to the button.
+#[derive(Debug, Component)]
+struct TextPopupPromptAnswer {
+    index: usize,
+    tag: Option<String>,
+}
+
+/// Extension methods on [`Commands`] for working with text popups directly.
+pub trait TextPopupCommandsExt {
+    /// Spawns a text popup right away and returns the commands of its root entity.
+    ///
+    /// Unlike writing a [`TextPopupEvent`] message, this gives the caller the root [`Entity`]
+    /// so the popup can be closed, updated or parented later on. It also skips the
+    /// [`TextPopupQueue`], the [`TextPopupCapacity`] limits and the [`TextPopupSuppression`]
+    /// check, so the caller decides whether the popup should be shown at all.
+    fn spawn_text_popup(&mut self, text_popup_event: TextPopupEvent) -> EntityCommands<'_>;
+
+    /// Spawns a text popup right away and returns a handle to the answer it closes with.
+    ///
+    /// Like [`TextPopupCommandsExt::spawn_text_popup`], this always shows the popup.
+    fn prompt_text_popup(&mut self, text_popup_event: TextPopupEvent) -> TextPopupPrompt;
+}
+
+impl TextPopupCommandsExt for Commands<'_, '_> {
+    fn spawn_text_popup(&mut self, text_popup_event: TextPopupEvent) -> EntityCommands<'_> {
+        let root_id = text_popup::generate_text_popup_from_event(self, &text_popup_event);
+        self.entity(root_id)
+    }
+
+    fn prompt_text_popup(&mut self, text_popup_event: TextPopupEvent) -> TextPopupPrompt {
+        let popup = text_popup::generate_text_popup_from_event(self, &text_popup_event);
+        let prompt = TextPopupPrompt {
+            popup,
+            state: default(),
+        };
+        self.entity(popup)
+            .insert(TextPopupPromptResolver(prompt.clone()));
+        prompt
+    }
+}
+
+/// Extension methods on [`App`] for configuring text popups.
+pub trait TextPopupAppExt {
+    /// Expires popups using [`TextPopupClock::custom::<T>`] against the `Time<T>` resource.
+    ///
+    /// Registering the same clock again does nothing, so `Time<Virtual>` and `Time<Real>`,
+    /// which [`TextPopupPlugin`] registers itself, are safe to pass as well.
+    fn add_text_popup_clock<T: Default + Send + Sync + 'static>(&mut self) -> &mut Self;
+}
+
+impl TextPopupAppExt for App {
+    fn add_text_popup_clock<T: Default + Send + Sync + 'static>(&mut self) -> &mut Self {
+        let mut clocks = self
+            .world_mut()
+            .get_resource_or_init::<TextPopupRegisteredClocks>();
+        if !clocks.0.insert(TypeId::of::<T>()) {
+            return self;
+        }
+        self.add_systems(
+            Update,
+            systems::cleanup_expired_text_popups::<T>.before(systems::handle_text_popup_events),
+        )
+    }
 }
 
+/// The clocks that already have an expiration system, so none of them ticks twice.
+#[derive(Debug, Default, Resource)]
+struct TextPopupRegisteredClocks(HashSet<TypeId>);
+
 pub struct TextPopupPlugin;
 
 impl Plugin for TextPopupPlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<TextPopupEvent>().add_systems(
-            Update,
-            (
-                systems::handle_text_popup_events,
-                systems::cleanup_expired_text_popups,
-                systems::cleanup_frame_expired_text_popups,
-                systems::text_popup_button_system,
-            ),
+        app.add_message::<TextPopupEvent>()
+            .add_message::<TextPopupUpdate>()
+            .add_message::<TextPopupSpawned>()
+            .add_message::<TextPopupClosed>()
+            .add_message::<TextPopupButtonPressed>()
+            .add_message::<TextPopupSubmitted>()
+            .add_message::<TextPopupSelected>()
+            .add_message::<TextPopupWizardEnded>()
+            .init_resource::<TextPopupSpawnCounter>()
+            .init_resource::<TextPopupStacking>()
+            .init_resource::<TextPopupQueue>()
+            .init_resource::<TextPopupCapacity>()
+            .init_resource::<TextPopupSuppression>()
+            .init_resource::<InputFocus>()
+            .add_observer(systems::report_closed_text_popups)
+            .add_text_popup_clock::<Virtual>()
+            .add_text_popup_clock::<Real>()
+            .add_systems(
+                Update,
+                (
+                    // Popups closing this frame are gone before new popups are merged into
+                    // duplicates or counted toward the capacity limits.
+                    systems::handle_text_popup_events
+                        .after(systems::cleanup_frame_expired_text_popups)
+                        .after(systems::text_popup_button_system)
+                        .after(systems::text_popup_close_button_system)
+                        .after(systems::dismiss_text_popups),
+                    systems::cleanup_frame_expired_text_popups,
+                    // Buttons are enabled or disabled before this frame's presses are handled.
+                    systems::update_text_popup_button_enabled
+                        .run_if(any_with_component::<TextPopupButtonEnabled>)
+                        .before(systems::text_popup_button_system)
+                        .before(systems::text_popup_button_input_system)
+                        .before(systems::edit_text_popup_inputs),
+                    systems::text_popup_button_system,
+                    systems::text_popup_close_button_system,
+                    systems::focus_clicked_text_popup_inputs,
+                    systems::edit_text_popup_inputs,
+                    systems::text_popup_choice_system,
+                    systems::text_popup_suppress_checkbox_system,
+                    (
+                        systems::draw_text_popup_inputs,
+                        systems::draw_text_popup_choices,
+                        systems::update_text_popup_wizards,
+                    ),
+                    systems::text_popup_button_input_system,
+                    systems::draw_text_popup_focus_ring,
+                    systems::style_text_popup_buttons,
+                    systems::pause_hovered_text_popups,
+                    systems::stack_text_popups,
+                    (
+                        systems::update_text_popups,
+                        systems::update_coalesced_text_popups,
+                    )
+                        .chain(),
+                    // Button presses go first, so they are not also counted as a dismissal.
+                    systems::dismiss_text_popups
+                        .after(systems::text_popup_button_system)
+                        .after(systems::text_popup_button_input_system)
+                        .after(systems::text_popup_close_button_system)
+                        .after(systems::focus_clicked_text_popup_inputs)
+                        .after(systems::edit_text_popup_inputs)
+                        .after(systems::text_popup_choice_system),
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        task::Wake,
+    };
+
+    fn queued_popup(content: &str, priority: i32) -> TextPopupEvent {
+        TextPopupEvent {
+            content: content.to_string(),
+            queue: true,
+            priority,
+            ..default()
+        }
+    }
+
+    fn drain(text_popup_queue: &mut TextPopupQueue) -> Vec<String> {
+        std::iter::from_fn(|| text_popup_queue.pop())
+            .map(|text_popup_event| text_popup_event.content)
+            .collect()
+    }
+
+    fn choice_state(selection: TextPopupSelection) -> TextPopupChoiceState {
+        TextPopupChoiceState {
+            selected: Vec::new(),
+            texts: Vec::new(),
+            choices: TextPopupChoices {
+                options: vec!["Easy".into(), "Normal".into(), "Hard".into()],
+                selection,
+                ..default()
+            },
+        }
+    }
+
+    #[test]
+    fn fifo_queue_pops_in_arrival_order() {
+        let mut text_popup_queue = TextPopupQueue::new(TextPopupQueueOrder::Fifo);
+        text_popup_queue.push(queued_popup("a", 0));
+        text_popup_queue.push(queued_popup("b", 10));
+        text_popup_queue.push(queued_popup("c", 0));
+
+        assert_eq!(drain(&mut text_popup_queue), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn priority_queue_pops_highest_first_and_fifo_among_equals() {
+        let mut text_popup_queue = TextPopupQueue::new(TextPopupQueueOrder::Priority);
+        text_popup_queue.push(queued_popup("a", 0));
+        text_popup_queue.push(queued_popup("b", 10));
+        text_popup_queue.push(queued_popup("c", 0));
+        text_popup_queue.push(queued_popup("d", 10));
+
+        assert_eq!(text_popup_queue.peek().unwrap().content, "b");
+        assert_eq!(drain(&mut text_popup_queue), ["b", "d", "a", "c"]);
+        assert!(text_popup_queue.is_empty());
+    }
+
+    #[test]
+    fn single_selection_keeps_only_the_last_toggled_option() {
+        let mut state = choice_state(TextPopupSelection::Single);
+        state.toggle(2);
+        state.toggle(0);
+        state.toggle(0);
+
+        assert_eq!(state.selected(), [0]);
+        assert_eq!(state.label(0), "(•) Easy");
+        assert_eq!(state.label(2), "( ) Hard");
+    }
+
+    #[test]
+    fn multiple_selection_toggles_options_and_stays_sorted() {
+        let mut state = choice_state(TextPopupSelection::Multiple);
+        state.toggle(2);
+        state.toggle(0);
+        state.toggle(1);
+        state.toggle(2);
+
+        assert_eq!(state.selected(), [0, 1]);
+        assert_eq!(state.label(1), "[x] Normal");
+        assert_eq!(state.label(2), "[ ] Hard");
+    }
+
+    fn input_state(input: TextPopupInput) -> TextPopupInputState {
+        TextPopupInputState {
+            value: String::new(),
+            caret: 0,
+            field: Entity::PLACEHOLDER,
+            character_count: None,
+            text_color: default(),
+            input,
+        }
+    }
+
+    fn type_text(state: &mut TextPopupInputState, text: &str) {
+        text.chars().for_each(|character| state.insert(character));
+    }
+
+    #[test]
+    fn input_stops_at_max_length_and_skips_filtered_characters() {
+        let mut state = input_state(TextPopupInput {
+            max_length: Some(4),
+            filter: Some(|character| character.is_ascii_digit()),
+            ..default()
+        });
+        type_text(&mut state, "1a2\n34 5");
+
+        assert_eq!(state.value(), "1234");
+        assert_eq!(state.character_count(), "4/4");
+    }
+
+    #[test]
+    fn input_edits_at_the_caret() {
+        let mut state = input_state(TextPopupInput::default());
+        type_text(&mut state, "héllo");
+        state.move_caret(1);
+        state.backspace();
+        state.delete();
+        type_text(&mut state, "Ja");
+        state.move_caret(usize::MAX);
+        state.backspace();
+
+        assert_eq!(state.value(), "Jall");
+        assert_eq!(state.caret, 4);
+    }
+
+    #[test]
+    fn only_multiline_inputs_accept_newlines() {
+        let mut single_line = input_state(TextPopupInput::default());
+        type_text(&mut single_line, "a\nb");
+        assert_eq!(single_line.value(), "ab");
+
+        let mut multiline = input_state(TextPopupInput {
+            multiline: true,
+            ..default()
+        });
+        type_text(&mut multiline, "a\nb");
+        assert_eq!(multiline.value(), "a\nb");
+    }
+
+    #[test]
+    fn masked_input_shows_the_mask_and_caret() {
+        let mut state = input_state(TextPopupInput {
+            mask: Some('*'),
+            placeholder: "Password".to_string(),
+            ..default()
+        });
+        assert_eq!(state.display(true), ("|Password".to_string(), true));
+
+        type_text(&mut state, "abc");
+        state.move_caret(1);
+        assert_eq!(state.display(true), ("*|**".to_string(), false));
+        assert_eq!(state.display(false), ("***".to_string(), false));
+    }
+
+    #[test]
+    fn suppressed_keys_survive_a_save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "bevy_text_popup_suppression_{}.txt",
+            std::process::id()
+        ));
+        let keys = ["plain", "two\nlines", "carriage\rreturn", "back\\slash\\n"];
+        let mut text_popup_suppression = TextPopupSuppression::default().with_path(&path);
+        text_popup_suppression.reset();
+        for key in keys {
+            text_popup_suppression.suppress(key);
+        }
+
+        let loaded = TextPopupSuppression::default().with_path(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            loaded.keys().collect::<BTreeSet<_>>(),
+            keys.into_iter().collect()
         );
+        assert!(!loaded.is_suppressed("two"));
+    }
+
+    #[test]
+    fn resolving_a_prompt_wakes_every_clone_awaiting_it() {
+        struct Flag(AtomicBool);
+
+        impl Wake for Flag {
+            fn wake(self: Arc<Self>) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let prompt = TextPopupPrompt {
+            popup: Entity::PLACEHOLDER,
+            state: default(),
+        };
+        let mut clones = [prompt.clone(), prompt.clone()];
+        let flags = [(); 2].map(|_| Arc::new(Flag(AtomicBool::new(false))));
+        for (clone, flag) in clones.iter_mut().zip(&flags) {
+            let waker = Waker::from(flag.clone());
+            let poll = Pin::new(clone).poll(&mut Context::from_waker(&waker));
+            assert!(poll.is_pending());
+        }
+
+        prompt.resolve(TextPopupPromptResult::Dismissed);
+        assert!(flags.iter().all(|flag| flag.0.load(Ordering::SeqCst)));
+        for clone in &clones {
+            assert_eq!(clone.result(), Some(TextPopupPromptResult::Dismissed));
+        }
     }
 }
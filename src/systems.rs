@@ -1,33 +1,233 @@
+// Bevy systems routinely take many complex parameters, so these lints are mostly noise here.
+#![allow(clippy::type_complexity, clippy::too_many_arguments)]
+
 use bevy::{
-    prelude::{Changed, Children, Commands, Entity, MessageReader, Query, Res},
+    ecs::system::SystemParam,
+    input::keyboard::{Key, KeyboardInput},
+    input_focus::InputFocus,
+    prelude::{
+        ButtonInput, Changed, Children, Commands, DetectChanges, DetectChangesMut, Entity, Gamepad,
+        GamepadButton, GlobalZIndex, Has, KeyCode, Local, MessageReader, MessageWriter,
+        MouseButton, Name, Node, On, ParamSet, Query, QueryState, Ref, Remove, Res, ResMut, Text,
+        Touches, Vec2, With, Without, World,
+    },
+    text::TextColor,
     time::Time,
-    ui::Interaction,
+    ui::{
+        BackgroundColor, BorderColor, ComputedNode, Display, FlexDirection, Interaction,
+        InteractionDisabled, Outline, RelativeCursorPosition, UiTransform, Val2,
+    },
+};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    mem::{discriminant, Discriminant},
 };
 
 use crate::{
-    text_popup::generate_text_popup_from_event, TextPopupButtonActionData, TextPopupEvent,
-    TextPopupExpires, TextPopupExpiresInFrames,
+    text_popup::{
+        coalesce_key, generate_text_popup_from_event, get_root_justify_content, insert_timeout,
+        set_text_node_location, visible_wizard_buttons,
+    },
+    TextPopup, TextPopupButtonActionData, TextPopupButtonEnabled, TextPopupButtonPressed,
+    TextPopupButtonStyles, TextPopupCapacity, TextPopupCapacityPolicy, TextPopupChoice,
+    TextPopupChoiceState, TextPopupCloseButtonNode, TextPopupCloseReason, TextPopupClosed,
+    TextPopupClosing, TextPopupCoalesced, TextPopupDismissTrigger, TextPopupEvent,
+    TextPopupExpires, TextPopupExpiresInFrames, TextPopupInputField, TextPopupInputState,
+    TextPopupLocation, TextPopupNodes, TextPopupPauseOnHover, TextPopupPaused,
+    TextPopupPromptAnswer, TextPopupPromptResolver, TextPopupPromptResult, TextPopupQueue,
+    TextPopupQueued, TextPopupSelected, TextPopupSpawnOrder, TextPopupStackOrder,
+    TextPopupStacking, TextPopupSubmitted, TextPopupSuppressCheckbox, TextPopupSuppression,
+    TextPopupTarget, TextPopupUpdate, TextPopupUsesClock, TextPopupWizardEnded,
+    TextPopupWizardOutcome, TextPopupWizardState,
 };
 
 pub fn handle_text_popup_events(
     mut commands: Commands,
     mut text_popup_events: MessageReader<TextPopupEvent>,
-    time: Res<Time>,
+    mut text_popup_queue: ResMut<TextPopupQueue>,
+    mut text_popup_capacity: ResMut<TextPopupCapacity>,
+    text_popup_suppression: Res<TextPopupSuppression>,
+    queued_text_popups: Query<(), With<TextPopupQueued>>,
+    coalesced_text_popups: Query<(Entity, &TextPopupCoalesced)>,
+    live_text_popups: Query<(Entity, &TextPopupLocation, &TextPopupSpawnOrder)>,
 ) {
-    for text_popup_event in text_popup_events.read() {
-        generate_text_popup_from_event(&mut commands, &time, text_popup_event);
+    let mut coalesced: HashMap<String, (Entity, TextPopupCoalesced)> = coalesced_text_popups
+        .iter()
+        .map(|(entity, coalesced)| (coalesced.key.clone(), (entity, coalesced.clone())))
+        .collect();
+    let mut live: Vec<_> = live_text_popups.iter().collect();
+    live.sort_by_key(|(_, _, spawn_order)| **spawn_order);
+    let mut live: Vec<_> = live
+        .into_iter()
+        .map(|(entity, location, _)| (entity, *location))
+        .collect();
+
+    // Popups held back by the capacity limit get the first chance at any room freed up since.
+    let waiting = std::mem::take(&mut text_popup_capacity.waiting);
+    for text_popup_event in waiting.iter().chain(text_popup_events.read()) {
+        if text_popup_suppression.is_suppressed_event(text_popup_event) {
+            continue;
+        }
+        if let Some(key) = coalesce_key(text_popup_event) {
+            if let Some((entity, coalesced)) = coalesced.get_mut(&key) {
+                coalesced.count += 1;
+                let mut entity_commands = commands.entity(*entity);
+                entity_commands.try_insert(coalesced.clone());
+                insert_timeout(
+                    &mut entity_commands,
+                    &text_popup_event.timeout,
+                    text_popup_event.clock,
+                );
+                continue;
+            }
+        }
+        if text_popup_event.queue {
+            text_popup_queue.push(text_popup_event.clone());
+            continue;
+        }
+        let Some(evicted) = make_room(&text_popup_capacity, &mut live, text_popup_event.location)
+        else {
+            if text_popup_capacity.policy == TextPopupCapacityPolicy::Queue {
+                text_popup_capacity.waiting.push(text_popup_event.clone());
+            }
+            continue;
+        };
+        for entity in evicted {
+            close_text_popup(&mut commands, entity, TextPopupCloseReason::Evicted);
+            // Later duplicates in this frame must not be merged into the evicted popup.
+            coalesced.retain(|_, (coalesced_entity, _)| *coalesced_entity != entity);
+        }
+        let root_id = generate_text_popup_from_event(&mut commands, text_popup_event);
+        live.push((root_id, text_popup_event.location));
+        if let Some(key) = coalesce_key(text_popup_event) {
+            let coalesced_text_popup = TextPopupCoalesced {
+                key: key.clone(),
+                content: text_popup_event.content.clone(),
+                count: 1,
+            };
+            coalesced.insert(key, (root_id, coalesced_text_popup));
+        }
+    }
+    // Queued popups may have been suppressed since they were sent.
+    while text_popup_queue.peek().is_some_and(|text_popup_event| {
+        text_popup_suppression.is_suppressed_event(text_popup_event)
+    }) {
+        text_popup_queue.pop();
+    }
+    if queued_text_popups.is_empty() {
+        let evicted = text_popup_queue.peek().and_then(|text_popup_event| {
+            make_room(&text_popup_capacity, &mut live, text_popup_event.location)
+        });
+        if let Some(evicted) = evicted {
+            for entity in evicted {
+                close_text_popup(&mut commands, entity, TextPopupCloseReason::Evicted);
+            }
+            if let Some(text_popup_event) = text_popup_queue.pop() {
+                let root_id = generate_text_popup_from_event(&mut commands, &text_popup_event);
+                commands.entity(root_id).insert(TextPopupQueued);
+            }
+        }
     }
 }
 
-pub fn cleanup_expired_text_popups(
+/// Checks the capacity limits for a new popup at `location`, returning the oldest popups to
+/// evict for it if the policy allows it, or `None` if there is no room.
+///
+/// `live` holds the popups on screen, oldest first, and loses the evicted popups.
+fn make_room(
+    text_popup_capacity: &TextPopupCapacity,
+    live: &mut Vec<(Entity, TextPopupLocation)>,
+    location: TextPopupLocation,
+) -> Option<Vec<Entity>> {
+    let mut remaining = live.clone();
+    let mut evicted = Vec::new();
+    loop {
+        let location_full = text_popup_capacity
+            .location_limit(location)
+            .is_some_and(|limit| {
+                remaining
+                    .iter()
+                    .filter(|(_, live_location)| *live_location == location)
+                    .count()
+                    >= limit
+            });
+        let global_full = text_popup_capacity
+            .global
+            .is_some_and(|limit| remaining.len() >= limit);
+        if !location_full && !global_full {
+            *live = remaining;
+            return Some(evicted);
+        }
+        if text_popup_capacity.policy != TextPopupCapacityPolicy::DropOldest {
+            return None;
+        }
+        let oldest = if location_full {
+            remaining
+                .iter()
+                .position(|(_, live_location)| *live_location == location)
+        } else {
+            (!remaining.is_empty()).then_some(0)
+        };
+        evicted.push(remaining.remove(oldest?).0);
+    }
+}
+
+fn close_text_popup(commands: &mut Commands, entity: Entity, reason: TextPopupCloseReason) {
+    commands
+        .entity(entity)
+        .try_insert(TextPopupClosing(reason))
+        .try_despawn();
+}
+
+pub fn cleanup_expired_text_popups<T: Default + Send + Sync + 'static>(
     mut commands: Commands,
-    text_popups: Query<(Entity, &TextPopupExpires)>,
-    time: Res<Time>,
+    mut text_popups: Query<
+        (Entity, &mut TextPopupExpires, &TextPopupUsesClock),
+        Without<TextPopupPaused>,
+    >,
+    time: Res<Time<T>>,
 ) {
-    let current_time = time.elapsed_secs_f64();
-    for (entity, text_popup) in text_popups.iter() {
-        if text_popup.expiration_time < current_time {
-            commands.entity(entity).despawn();
+    for (entity, mut text_popup, clock) in text_popups.iter_mut() {
+        if clock.0 != TypeId::of::<T>() {
+            continue;
+        }
+        if text_popup.timer.tick(time.delta()).is_finished() {
+            close_text_popup(&mut commands, entity, TextPopupCloseReason::TimedOut);
+        }
+    }
+}
+
+/// Runs the [`TextPopupButtonEnabled::When`] condition of every button, toggling
+/// [`InteractionDisabled`] to match.
+pub fn update_text_popup_button_enabled(
+    world: &mut World,
+    mut buttons: Local<
+        QueryState<(
+            Entity,
+            &'static TextPopupButtonEnabled,
+            Has<InteractionDisabled>,
+        )>,
+    >,
+) {
+    let buttons: Vec<_> = buttons
+        .iter(world)
+        .filter_map(|(entity, enabled, disabled)| match enabled {
+            TextPopupButtonEnabled::When(condition) => Some((entity, *condition, disabled)),
+            _ => None,
+        })
+        .collect();
+    for (entity, condition, disabled) in buttons {
+        let Ok(enabled) = world.run_system(condition) else {
+            continue;
+        };
+        let Ok(mut button) = world.get_entity_mut(entity) else {
+            continue;
+        };
+        if enabled && disabled {
+            button.remove::<InteractionDisabled>();
+        } else if !enabled && !disabled {
+            button.insert(InteractionDisabled);
         }
     }
 }
@@ -35,35 +235,1567 @@ pub fn cleanup_expired_text_popups(
 pub fn text_popup_button_system(
     mut interaction_query: Query<
         (&Interaction, &TextPopupButtonActionData, &Children),
-        Changed<Interaction>,
+        (Changed<Interaction>, Without<InteractionDisabled>),
     >,
+    mut activation: TextPopupButtonActivation,
+) {
+    for (interaction, button, _children) in &mut interaction_query {
+        if *interaction == Interaction::Pressed {
+            activation.activate(button);
+        }
+    }
+}
+
+pub fn text_popup_close_button_system(
     mut commands: Commands,
+    close_buttons: Query<(&Interaction, &TextPopupCloseButtonNode), Changed<Interaction>>,
 ) {
-    for (interaction, TextPopupButtonActionData { root_id, action }, _children) in
-        &mut interaction_query
-    {
-        match *interaction {
-            Interaction::Pressed => {
-                action(&mut commands, *root_id);
-            },
-            Interaction::Hovered => {
-                // Hover color change here.
+    for (interaction, close_button) in &close_buttons {
+        if *interaction == Interaction::Pressed {
+            close_text_popup(
+                &mut commands,
+                close_button.root_id,
+                TextPopupCloseReason::Closed,
+            );
+        }
+    }
+}
+
+pub fn dismiss_text_popups(
+    mut commands: Commands,
+    text_popups: Query<(Entity, &TextPopupDismissTrigger, &TextPopupNodes)>,
+    interactions: Query<&Interaction>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepads: Query<&Gamepad>,
+    mut keyboard_events: MessageReader<KeyboardInput>,
+    input_focus: Res<InputFocus>,
+    input_fields: Query<(Entity, &TextPopupInputField)>,
+    choices: Query<(Entity, &TextPopupChoice)>,
+    checkboxes: Query<(Entity, &TextPopupSuppressCheckbox)>,
+    buttons: Query<&TextPopupButtonActionData>,
+    mut button_pressed_events: MessageReader<TextPopupButtonPressed>,
+) {
+    let pressed: Vec<_> = button_pressed_events
+        .read()
+        .map(|pressed| pressed.popup)
+        .collect();
+    let typing_in = input_focus
+        .get()
+        .and_then(|focus| input_fields.get(focus).ok())
+        .map(|(_, input_field)| input_field.root_id);
+    let navigating_in = input_focus.get().and_then(|focus| {
+        choices
+            .get(focus)
+            .map(|(_, choice)| choice.root_id)
+            .or_else(|_| buttons.get(focus).map(|button| button.root_id))
+            .ok()
+    });
+    let focused_popup = typing_in.or(navigating_in);
+    // Input used by the focused element of a popup doesn't count as input that dismisses it:
+    // keys typed into its text field, the keys and gamepad buttons moving between and
+    // activating its choices and buttons, and the keys bound to its buttons.
+    let mut used_keys: Vec<_> = keyboard_events
+        .read()
+        .filter(|event| {
+            typing_in.is_some()
+                && event.state.is_pressed()
+                && (edits_text(event)
+                    || matches!(
+                        event.logical_key,
+                        Key::Shift | Key::Control | Key::Alt | Key::AltGraph | Key::Super
+                    ))
+        })
+        .map(|event| event.key_code)
+        .collect();
+    if navigating_in.is_some() {
+        used_keys.extend([
+            KeyCode::ArrowLeft,
+            KeyCode::ArrowUp,
+            KeyCode::ArrowRight,
+            KeyCode::ArrowDown,
+            KeyCode::Enter,
+            KeyCode::NumpadEnter,
+        ]);
+    }
+    if let Some(focused_popup) = focused_popup {
+        used_keys.extend(
+            buttons
+                .iter()
+                .filter(|button| button.root_id == focused_popup)
+                .flat_map(|button| button.keys.iter().copied()),
+        );
+    }
+    let used_gamepad_buttons: &[GamepadButton] = if focused_popup.is_some() {
+        &[
+            GamepadButton::South,
+            GamepadButton::DPadLeft,
+            GamepadButton::DPadUp,
+            GamepadButton::DPadRight,
+            GamepadButton::DPadDown,
+        ]
+    } else {
+        &[]
+    };
+    let key_just_pressed = |popup| {
+        keyboard_input
+            .get_just_pressed()
+            .any(|key| focused_popup != Some(popup) || !used_keys.contains(key))
+    };
+    let gamepad_just_pressed = |popup| {
+        gamepads.iter().any(|gamepad| {
+            gamepad.get_just_pressed().any(|button| {
+                focused_popup != Some(popup) || !used_gamepad_buttons.contains(button)
+            })
+        })
+    };
+    let is_pressed = |entity| {
+        interactions
+            .get(entity)
+            .is_ok_and(|interaction| *interaction == Interaction::Pressed)
+    };
+    // A click or touch on one of a popup's text fields, choices or checkboxes is used by that
+    // element.
+    let control_pressed = |popup| {
+        input_fields
+            .iter()
+            .map(|(entity, input_field)| (entity, input_field.root_id))
+            .chain(
+                choices
+                    .iter()
+                    .map(|(entity, choice)| (entity, choice.root_id)),
+            )
+            .chain(
+                checkboxes
+                    .iter()
+                    .map(|(entity, checkbox)| (entity, checkbox.root_id)),
+            )
+            .any(|(entity, root_id)| root_id == popup && is_pressed(entity))
+    };
+    let pointer_just_pressed =
+        mouse_input.get_just_pressed().len() > 0 || touches.any_just_pressed();
+    for (entity, dismiss_on, nodes) in &text_popups {
+        if pressed.contains(&entity) {
+            continue;
+        }
+        let dismissed = match dismiss_on {
+            TextPopupDismissTrigger::Never => false,
+            TextPopupDismissTrigger::ClickPopup => is_pressed(nodes.panel),
+            // The root spans the whole screen, so a press on it that misses the panel is outside.
+            TextPopupDismissTrigger::ClickOutside => is_pressed(entity) && !is_pressed(nodes.panel),
+            TextPopupDismissTrigger::AnyKey => key_just_pressed(entity),
+            TextPopupDismissTrigger::AnyInput => {
+                key_just_pressed(entity)
+                    || (pointer_just_pressed && !control_pressed(entity))
+                    || gamepad_just_pressed(entity)
             },
-            Interaction::None => {
-                // Back to normal after a hover.
+        };
+        if dismissed {
+            close_text_popup(&mut commands, entity, TextPopupCloseReason::Dismissed);
+        }
+    }
+}
+
+/// Handles keyboard and gamepad input for the buttons of the topmost popup with buttons.
+///
+/// Arrow keys and the D-pad move [`InputFocus`] along the action row, Enter and the South
+/// button activate the focused button, and otherwise a button bound to a just pressed key is
+/// activated.
+///
+/// While a popup text field has focus, keyboard navigation is off and keys that edit the text
+/// are left to the field.
+pub fn text_popup_button_input_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut keyboard_events: MessageReader<KeyboardInput>,
+    gamepads: Query<&Gamepad>,
+    mut input_focus: ResMut<InputFocus>,
+    input_fields: Query<(), With<TextPopupInputField>>,
+    buttons: Query<(Entity, &TextPopupButtonActionData, Has<InteractionDisabled>)>,
+    choices: Query<(Entity, &TextPopupChoice)>,
+    text_popups: Query<(&GlobalZIndex, &TextPopupSpawnOrder, &TextPopupNodes), With<TextPopup>>,
+    action_nodes: Query<&Node>,
+    mut activation: TextPopupButtonActivation,
+) {
+    let typing = input_focus
+        .get()
+        .is_some_and(|focus| input_fields.contains(focus));
+    let typed: Vec<_> = keyboard_events
+        .read()
+        .filter(|event| typing && event.state.is_pressed() && edits_text(event))
+        .map(|event| event.key_code)
+        .collect();
+    let gamepad_just_pressed = |button| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+    if keyboard_input.get_just_pressed().len() == 0
+        && gamepads
+            .iter()
+            .all(|gamepad| gamepad.get_just_pressed().next().is_none())
+    {
+        return;
+    }
+    let Some((topmost, nodes)) = buttons
+        .iter()
+        .map(|(_, button, _)| button.root_id)
+        .chain(choices.iter().map(|(_, choice)| choice.root_id))
+        .filter_map(|root_id| {
+            let (z_index, spawn_order, nodes) = text_popups.get(root_id).ok()?;
+            Some(((z_index.0, *spawn_order), (root_id, nodes)))
+        })
+        .max_by_key(|(order, _)| *order)
+        .map(|(_, topmost)| topmost)
+    else {
+        return;
+    };
+    // Everything focusable in the popup, in on-screen order: the choices, then the buttons.
+    let mut popup_choices: Vec<_> = choices
+        .iter()
+        .filter(|(_, choice)| choice.root_id == topmost)
+        .collect();
+    popup_choices.sort_by_key(|(_, choice)| choice.index);
+    let mut enabled: Vec<_> = buttons
+        .iter()
+        .filter(|(entity, button, disabled)| {
+            // Hidden buttons, e.g. a wizard's Finish before the last page, are skipped.
+            button.root_id == topmost
+                && !disabled
+                && action_nodes
+                    .get(*entity)
+                    .is_ok_and(|node| node.display != Display::None)
+        })
+        .map(|(entity, button, _)| (entity, button))
+        .collect();
+    enabled.sort_by_key(|(_, button)| button.index);
+    // Reversed action rows list the buttons backwards on screen.
+    let reversed = action_nodes.get(nodes.actions).is_ok_and(|node| {
+        matches!(
+            node.flex_direction,
+            FlexDirection::RowReverse | FlexDirection::ColumnReverse
+        )
+    });
+    if reversed {
+        enabled.reverse();
+    }
+    let focusable: Vec<_> = popup_choices
+        .into_iter()
+        .map(|(entity, choice)| (entity, Focusable::Choice(choice)))
+        .chain(
+            enabled
+                .into_iter()
+                .map(|(entity, button)| (entity, Focusable::Button(button))),
+        )
+        .collect();
+    let focused = input_focus
+        .get()
+        .and_then(|focus| focusable.iter().position(|(entity, _)| *entity == focus));
+
+    let key_just_pressed =
+        |keys: &[KeyCode]| !typing && keyboard_input.any_just_pressed(keys.iter().copied());
+    let previous = key_just_pressed(&[KeyCode::ArrowLeft, KeyCode::ArrowUp])
+        || gamepad_just_pressed(GamepadButton::DPadLeft)
+        || gamepad_just_pressed(GamepadButton::DPadUp);
+    let next = key_just_pressed(&[KeyCode::ArrowRight, KeyCode::ArrowDown])
+        || gamepad_just_pressed(GamepadButton::DPadRight)
+        || gamepad_just_pressed(GamepadButton::DPadDown);
+    if (previous || next) && !focusable.is_empty() {
+        let index = match focused {
+            Some(index) if next => (index + 1).min(focusable.len() - 1),
+            Some(index) => index.saturating_sub(1),
+            None => 0,
+        };
+        input_focus.set(focusable[index].0);
+        return;
+    }
+
+    if let Some(index) = focused {
+        if key_just_pressed(&[KeyCode::Enter, KeyCode::NumpadEnter])
+            || gamepad_just_pressed(GamepadButton::South)
+        {
+            match focusable[index].1 {
+                Focusable::Choice(choice) => activation.toggle_choice(choice),
+                Focusable::Button(button) => activation.activate(button),
+            }
+            return;
+        }
+    }
+    let button = focusable.iter().find_map(|(_, focusable)| match focusable {
+        Focusable::Button(button)
+            if button
+                .keys
+                .iter()
+                .any(|key| keyboard_input.just_pressed(*key) && !typed.contains(key)) =>
+        {
+            Some(*button)
+        },
+        _ => None,
+    });
+    if let Some(button) = button {
+        activation.activate(button);
+    }
+}
+
+enum Focusable<'a> {
+    Choice(&'a TextPopupChoice),
+    Button(&'a TextPopupButtonActionData),
+}
+
+pub fn text_popup_choice_system(
+    mut input_focus: ResMut<InputFocus>,
+    choices: Query<(Entity, &Interaction, &TextPopupChoice), Changed<Interaction>>,
+    mut activation: TextPopupButtonActivation,
+) {
+    for (entity, interaction, choice) in &choices {
+        if *interaction == Interaction::Pressed {
+            input_focus.set(entity);
+            activation.toggle_choice(choice);
+        }
+    }
+}
+
+pub fn text_popup_suppress_checkbox_system(
+    mut text_popup_suppression: ResMut<TextPopupSuppression>,
+    mut checkboxes: Query<(Ref<Interaction>, &mut TextPopupSuppressCheckbox)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, mut checkbox) in &mut checkboxes {
+        let pressed = interaction.is_changed() && *interaction == Interaction::Pressed;
+        if pressed {
+            checkbox.checked = !checkbox.checked;
+            if checkbox.checked {
+                text_popup_suppression.suppress(checkbox.key.clone());
+            } else {
+                text_popup_suppression.unsuppress(&checkbox.key);
+            }
+        }
+        // New checkboxes are spawned without text, the label lives on the resource.
+        if pressed || checkbox.is_added() {
+            if let Ok(mut text) = texts.get_mut(checkbox.text) {
+                let marker = if checkbox.checked { "[x]" } else { "[ ]" };
+                text.0 = format!("{} {}", marker, text_popup_suppression.label);
+            }
+        }
+    }
+}
+
+pub fn draw_text_popup_choices(
+    states: Query<&TextPopupChoiceState, Changed<TextPopupChoiceState>>,
+    mut texts: Query<&mut Text>,
+) {
+    for state in &states {
+        for (index, entity) in state.texts.iter().enumerate() {
+            if let Ok(mut text) = texts.get_mut(*entity) {
+                text.0 = state.label(index);
+            }
+        }
+    }
+}
+
+/// Whether a key press is handled by a focused popup text field.
+fn edits_text(event: &KeyboardInput) -> bool {
+    // Some platforms report text like "\u{1b}" for Escape, which must stay usable as a binding.
+    let typed_text = event
+        .text
+        .as_ref()
+        .is_some_and(|text| text.chars().any(|character| !character.is_control()));
+    typed_text
+        || matches!(
+            event.logical_key,
+            Key::Enter
+                | Key::Backspace
+                | Key::Delete
+                | Key::ArrowLeft
+                | Key::ArrowRight
+                | Key::Home
+                | Key::End
+        )
+}
+
+pub fn focus_clicked_text_popup_inputs(
+    mut input_focus: ResMut<InputFocus>,
+    input_fields: Query<(Entity, &Interaction), (Changed<Interaction>, With<TextPopupInputField>)>,
+) {
+    for (entity, interaction) in &input_fields {
+        if *interaction == Interaction::Pressed {
+            input_focus.set(entity);
+        }
+    }
+}
+
+/// Applies key presses to the focused popup text field, submitting it on Enter.
+pub fn edit_text_popup_inputs(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut keyboard_events: MessageReader<KeyboardInput>,
+    input_focus: Res<InputFocus>,
+    input_fields: Query<&TextPopupInputField>,
+    buttons: Query<(&TextPopupButtonActionData, &Node, Has<InteractionDisabled>)>,
+    mut inputs: ParamSet<(Query<&mut TextPopupInputState>, TextPopupButtonActivation)>,
+) {
+    let Some(root_id) = input_focus
+        .get()
+        .and_then(|focus| input_fields.get(focus).ok())
+        .map(|input_field| input_field.root_id)
+    else {
+        keyboard_events.clear();
+        return;
+    };
+    let shift = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let mut submitted = false;
+    {
+        let mut states = inputs.p0();
+        let Ok(mut state) = states.get_mut(root_id) else {
+            keyboard_events.clear();
+            return;
+        };
+        // Keys after a submit are read too, so they don't leak into the next frame.
+        for event in keyboard_events.read() {
+            if submitted || !event.state.is_pressed() {
+                continue;
+            }
+            match &event.logical_key {
+                Key::Enter if shift && state.input.multiline => state.insert('\n'),
+                Key::Enter => submitted = true,
+                Key::Backspace => state.backspace(),
+                Key::Delete => state.delete(),
+                Key::ArrowLeft => {
+                    let caret = state.caret.saturating_sub(1);
+                    state.move_caret(caret);
+                },
+                Key::ArrowRight => {
+                    let caret = state.caret + 1;
+                    state.move_caret(caret);
+                },
+                Key::Home => state.move_caret(0),
+                Key::End => state.move_caret(usize::MAX),
+                _ => {
+                    for character in event.text.iter().flat_map(|text| text.chars()) {
+                        state.insert(character);
+                    }
+                },
+            }
+        }
+    }
+    if !submitted {
+        return;
+    }
+    let mut activation = inputs.p1();
+    // The confirm button, or whichever shown button Enter is bound to, e.g. a wizard's Next.
+    // Enter does nothing while that button is disabled, and only submits the field directly
+    // when the popup has no such button.
+    let confirm_button = buttons
+        .iter()
+        .filter(|(button, node, _)| {
+            button.root_id == root_id
+                && node.display != Display::None
+                && (button.close_reason == TextPopupCloseReason::Confirmed
+                    || button.keys.contains(&KeyCode::Enter))
+        })
+        .min_by_key(|(button, _, disabled)| {
+            (
+                *disabled,
+                button.close_reason != TextPopupCloseReason::Confirmed,
+            )
+        });
+    match confirm_button {
+        Some((_, _, true)) => {},
+        Some((confirm_button, _, false)) => activation.activate(confirm_button),
+        None => activation.submit(root_id),
+    }
+}
+
+pub fn draw_text_popup_inputs(
+    input_focus: Res<InputFocus>,
+    states: Query<&TextPopupInputState>,
+    mut texts: Query<(&mut Text, &mut TextColor)>,
+) {
+    for state in &states {
+        if let Some(Ok((mut text, _))) = state.character_count.map(|entity| texts.get_mut(entity)) {
+            let character_count = state.character_count();
+            if text.0 != character_count {
+                text.0 = character_count;
+            }
+        }
+        let Ok((mut text, mut text_color)) = texts.get_mut(state.field) else {
+            continue;
+        };
+        let (display, placeholder) = state.display(input_focus.get() == Some(state.field));
+        if text.0 != display {
+            text.0 = display;
+        }
+        text_color.set_if_neq(if placeholder {
+            state.input.placeholder_color
+        } else {
+            state.text_color
+        });
+    }
+}
+
+/// Activates popup buttons and choices, whether by click, key binding or focus.
+#[derive(SystemParam)]
+pub struct TextPopupButtonActivation<'w, 's> {
+    commands: Commands<'w, 's>,
+    button_pressed_events: MessageWriter<'w, TextPopupButtonPressed>,
+    submitted_events: MessageWriter<'w, TextPopupSubmitted>,
+    selected_events: MessageWriter<'w, TextPopupSelected>,
+    inputs: Query<'w, 's, &'static TextPopupInputState>,
+    choices: Query<'w, 's, &'static mut TextPopupChoiceState>,
+}
+
+impl TextPopupButtonActivation<'_, '_> {
+    fn activate(
+        &mut self,
+        TextPopupButtonActionData {
+            root_id,
+            index,
+            action,
+            tag,
+            close_reason,
+            ..
+        }: &TextPopupButtonActionData,
+    ) {
+        self.button_pressed_events.write(TextPopupButtonPressed {
+            popup: *root_id,
+            button_index: *index,
+            tag: tag.clone(),
+        });
+        // Only confirming sends back the text and the selection, so cancelling or turning a
+        // wizard page doesn't look like an answer.
+        if *close_reason == TextPopupCloseReason::Confirmed {
+            self.write_submitted(*root_id);
+            self.write_selected(*root_id, Some(*index));
+        }
+        // Tag the root so a despawn from the action reports this button as the reason.
+        self.commands.entity(*root_id).try_insert((
+            TextPopupClosing(*close_reason),
+            TextPopupPromptAnswer {
+                index: *index,
+                tag: tag.clone(),
             },
+        ));
+        action.run(&mut self.commands, *root_id);
+        self.commands
+            .entity(*root_id)
+            .try_remove::<(TextPopupClosing, TextPopupPromptAnswer)>();
+    }
+
+    /// Submits a popup's text field without a confirm button, closing the popup.
+    fn submit(&mut self, root_id: Entity) {
+        self.write_submitted(root_id);
+        self.write_selected(root_id, None);
+        close_text_popup(&mut self.commands, root_id, TextPopupCloseReason::Confirmed);
+    }
+
+    fn write_submitted(&mut self, root_id: Entity) {
+        if let Ok(state) = self.inputs.get(root_id) {
+            self.submitted_events.write(TextPopupSubmitted {
+                popup: root_id,
+                value: state.value.clone(),
+            });
+        }
+    }
+
+    fn write_selected(&mut self, root_id: Entity, button_index: Option<usize>) {
+        if let Ok(state) = self.choices.get(root_id) {
+            self.selected_events.write(TextPopupSelected {
+                popup: root_id,
+                selected: state.selected.clone(),
+                button_index,
+            });
+        }
+    }
+
+    fn toggle_choice(&mut self, choice: &TextPopupChoice) {
+        if let Ok(mut state) = self.choices.get_mut(choice.root_id) {
+            state.toggle(choice.index);
+        }
+    }
+}
+
+pub fn draw_text_popup_focus_ring(
+    mut commands: Commands,
+    input_focus: Res<InputFocus>,
+    buttons: Query<(Entity, &TextPopupButtonStyles, Has<Outline>)>,
+) {
+    for (entity, styles, has_outline) in &buttons {
+        let focused = input_focus.get() == Some(entity);
+        if focused && !has_outline {
+            commands.entity(entity).try_insert(styles.focus_outline);
+        } else if !focused && has_outline {
+            commands.entity(entity).try_remove::<Outline>();
+        }
+    }
+}
+
+pub fn style_text_popup_buttons(
+    mut buttons: Query<(
+        &Interaction,
+        Has<InteractionDisabled>,
+        &TextPopupButtonStyles,
+        &mut BorderColor,
+        &mut UiTransform,
+    )>,
+    mut texts: Query<(&mut TextColor, &mut BackgroundColor)>,
+) {
+    for (interaction, disabled, styles, mut border_color, mut transform) in &mut buttons {
+        let style = styles.resolve(*interaction, disabled);
+        if let Some(color) = style.border_color {
+            border_color.set_if_neq(color);
+        }
+        if let Some(scale) = style.scale {
+            if transform.scale != Vec2::splat(scale) {
+                transform.scale = Vec2::splat(scale);
+            }
+        }
+        let Ok((mut text_color, mut background_color)) = texts.get_mut(styles.text) else {
+            continue;
+        };
+        if let Some(color) = style.text_color {
+            text_color.set_if_neq(color);
+        }
+        if let Some(color) = style.background_color {
+            background_color.set_if_neq(color);
         }
     }
 }
 
 pub fn cleanup_frame_expired_text_popups(
     mut commands: Commands,
-    mut text_popups: Query<(Entity, &mut TextPopupExpiresInFrames)>,
+    mut text_popups: Query<(Entity, &mut TextPopupExpiresInFrames), Without<TextPopupPaused>>,
 ) {
     for (entity, mut text_popup) in text_popups.iter_mut() {
         text_popup.frames_remaining = text_popup.frames_remaining.saturating_sub(1);
         if text_popup.frames_remaining == 0 {
-            commands.entity(entity).despawn();
+            close_text_popup(&mut commands, entity, TextPopupCloseReason::FrameExpired);
+        }
+    }
+}
+
+pub fn report_closed_text_popups(
+    remove: On<Remove, TextPopup>,
+    text_popups: Query<(
+        Option<&Name>,
+        Option<&TextPopupClosing>,
+        Option<&TextPopupPromptResolver>,
+        Option<&TextPopupPromptAnswer>,
+        Option<&TextPopupWizardState>,
+    )>,
+    mut closed_events: MessageWriter<TextPopupClosed>,
+    mut wizard_ended_events: MessageWriter<TextPopupWizardEnded>,
+) {
+    let Ok((name, closing, resolver, answer, wizard)) = text_popups.get(remove.entity) else {
+        return;
+    };
+    let reason = closing.map_or(TextPopupCloseReason::Despawned, |closing| closing.0);
+    if let Some(TextPopupPromptResolver(prompt)) = resolver {
+        prompt.resolve(match (answer, reason) {
+            (Some(answer), _) => TextPopupPromptResult::Button {
+                index: answer.index,
+                tag: answer.tag.clone(),
+            },
+            (None, TextPopupCloseReason::TimedOut | TextPopupCloseReason::FrameExpired) => {
+                TextPopupPromptResult::TimedOut
+            },
+            (None, _) => TextPopupPromptResult::Dismissed,
+        });
+    }
+    if let Some(wizard) = wizard {
+        wizard_ended_events.write(TextPopupWizardEnded {
+            popup: remove.entity,
+            outcome: if reason == TextPopupCloseReason::Confirmed {
+                TextPopupWizardOutcome::Finished
+            } else {
+                TextPopupWizardOutcome::Cancelled
+            },
+            page: wizard.page(),
+        });
+    }
+    closed_events.write(TextPopupClosed {
+        entity: remove.entity,
+        name: name.cloned(),
+        reason,
+    });
+}
+
+/// Swaps in the content, page indicator and navigation buttons of the current wizard page.
+pub fn update_text_popup_wizards(
+    wizards: Query<(&TextPopupWizardState, &TextPopupNodes), Changed<TextPopupWizardState>>,
+    mut texts: Query<&mut Text>,
+    mut nodes: Query<&mut Node>,
+) {
+    for (state, popup_nodes) in &wizards {
+        if let Some(page) = state.pages.get(state.page) {
+            if let Ok(mut text) = texts.get_mut(popup_nodes.text) {
+                text.0.clone_from(&page.content);
+            }
+        }
+        if let Some(Ok(mut text)) = state.indicator.map(|entity| texts.get_mut(entity)) {
+            text.0 = state.indicator_text();
+        }
+        let visible = visible_wizard_buttons(state.page, state.page_count());
+        for (button, visible) in [state.back, state.next, state.finish]
+            .into_iter()
+            .zip(visible)
+        {
+            if let Ok(mut node) = nodes.get_mut(button) {
+                let display = if visible {
+                    Display::Flex
+                } else {
+                    Display::None
+                };
+                if node.display != display {
+                    node.display = display;
+                }
+            }
         }
     }
 }
+
+pub fn pause_hovered_text_popups(
+    mut commands: Commands,
+    text_popups: Query<
+        (Entity, &TextPopupNodes, Has<TextPopupPaused>),
+        With<TextPopupPauseOnHover>,
+    >,
+    panels: Query<&RelativeCursorPosition>,
+    buttons: Query<(Entity, &TextPopupButtonActionData, &Interaction)>,
+    input_focus: Res<InputFocus>,
+) {
+    for (entity, nodes, paused) in text_popups.iter() {
+        let hovered = panels
+            .get(nodes.panel)
+            .is_ok_and(RelativeCursorPosition::cursor_over)
+            || buttons.iter().any(|(button, action_data, interaction)| {
+                action_data.root_id == entity
+                    && (*interaction != Interaction::None || input_focus.get() == Some(button))
+            });
+        if hovered && !paused {
+            commands.entity(entity).try_insert(TextPopupPaused);
+        } else if !hovered && paused {
+            commands.entity(entity).try_remove::<TextPopupPaused>();
+        }
+    }
+}
+
+pub fn stack_text_popups(
+    stacking: Res<TextPopupStacking>,
+    text_popups: Query<(&TextPopupLocation, &TextPopupSpawnOrder, &TextPopupNodes)>,
+    mut panels: Query<(&ComputedNode, &mut UiTransform)>,
+) {
+    let mut stacks: HashMap<Discriminant<TextPopupLocation>, Vec<_>> = HashMap::new();
+    for (location, spawn_order, nodes) in text_popups.iter() {
+        if matches!(location, TextPopupLocation::Custom(_, _)) {
+            continue;
+        }
+        stacks.entry(discriminant(location)).or_default().push((
+            *spawn_order,
+            *location,
+            nodes.panel,
+        ));
+    }
+    for mut stack in stacks.into_values() {
+        stack.sort_by_key(|(spawn_order, _, _)| *spawn_order);
+        if stacking.order == TextPopupStackOrder::NewestFirst {
+            stack.reverse();
+        }
+        let mut offset = 0.;
+        for (_, location, panel) in stack {
+            let Ok((computed_node, mut ui_transform)) = panels.get_mut(panel) else {
+                continue;
+            };
+            let direction = match location {
+                TextPopupLocation::BottomLeft
+                | TextPopupLocation::Bottom
+                | TextPopupLocation::BottomRight => -1.,
+                _ => 1.,
+            };
+            let translation = if stacking.enabled {
+                Val2::px(0., direction * offset)
+            } else {
+                Val2::ZERO
+            };
+            if ui_transform.translation != translation {
+                ui_transform.translation = translation;
+            }
+            offset +=
+                computed_node.size().y * computed_node.inverse_scale_factor() + stacking.spacing;
+        }
+    }
+}
+
+pub fn update_coalesced_text_popups(
+    text_popups: Query<(&TextPopupCoalesced, &TextPopupNodes), Changed<TextPopupCoalesced>>,
+    mut texts: Query<&mut Text>,
+) {
+    for (coalesced, nodes) in text_popups.iter() {
+        let Ok(mut text) = texts.get_mut(nodes.text) else {
+            continue;
+        };
+        text.0 = match coalesced.count {
+            0 | 1 => coalesced.content.clone(),
+            count => format!("{} (x{})", coalesced.content, count),
+        };
+    }
+}
+
+pub fn update_text_popups(
+    mut commands: Commands,
+    mut text_popup_updates: MessageReader<TextPopupUpdate>,
+    mut text_popups: Query<
+        (
+            Entity,
+            Option<&Name>,
+            &TextPopupNodes,
+            &mut Node,
+            &mut TextPopupLocation,
+            Option<&mut TextPopupCoalesced>,
+        ),
+        With<TextPopup>,
+    >,
+    mut panels: Query<(&mut Node, &mut BackgroundColor, &mut BorderColor), Without<TextPopup>>,
+    mut texts: Query<(&mut Text, &mut TextColor)>,
+) {
+    for text_popup_update in text_popup_updates.read() {
+        for (entity, name, nodes, mut root_node, mut location, coalesced) in text_popups.iter_mut()
+        {
+            let targeted = match &text_popup_update.target {
+                TextPopupTarget::Entity(target) => *target == entity,
+                TextPopupTarget::Name(target) => name == Some(target),
+            };
+            if !targeted {
+                continue;
+            }
+            if let Ok((mut text, mut text_color)) = texts.get_mut(nodes.text) {
+                if let Some(content) = &text_popup_update.content {
+                    // Coalesced popups re-render their counter from the stored content.
+                    match coalesced {
+                        Some(mut coalesced) => coalesced.content = content.clone(),
+                        None => text.0 = content.clone(),
+                    }
+                }
+                if let Some(new_text_color) = text_popup_update.text_color {
+                    *text_color = new_text_color;
+                }
+            }
+            if let Ok((mut panel_node, mut background_color, mut border_color)) =
+                panels.get_mut(nodes.panel)
+            {
+                if let Some(new_background_color) = text_popup_update.background_color {
+                    *background_color = new_background_color;
+                }
+                if let Some(new_border_color) = text_popup_update.border_color {
+                    *border_color = new_border_color;
+                }
+                if let Some(border) = text_popup_update.border {
+                    panel_node.border = border;
+                }
+                if let Some(new_location) = text_popup_update.location {
+                    *location = new_location;
+                    root_node.justify_content = get_root_justify_content(new_location);
+                    set_text_node_location(&mut panel_node, new_location);
+                }
+            }
+            if let Some(timeout) = &text_popup_update.timeout {
+                insert_timeout(
+                    &mut commands.entity(entity),
+                    timeout,
+                    text_popup_update.clock,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        input::{mouse::MouseButtonInput, ButtonState, InputPlugin},
+        prelude::*,
+        time::TimeUpdateStrategy,
+    };
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        TextPopupAction, TextPopupButton, TextPopupChoices, TextPopupCoalesce,
+        TextPopupCommandsExt, TextPopupInput, TextPopupPlugin, TextPopupTimeout, TextPopupWizard,
+    };
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, TextPopupPlugin));
+        app
+    }
+
+    fn app_with_capacity(text_popup_capacity: TextPopupCapacity) -> App {
+        let mut app = app();
+        app.insert_resource(text_popup_capacity);
+        app
+    }
+
+    fn press(app: &mut App, key_code: KeyCode, logical_key: Key) {
+        let text = match &logical_key {
+            Key::Character(character) => Some(character.clone()),
+            _ => None,
+        };
+        app.world_mut().write_message(KeyboardInput {
+            key_code,
+            logical_key,
+            state: ButtonState::Pressed,
+            text,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+    }
+
+    fn coalesced_popup(key: &str) -> TextPopupEvent {
+        TextPopupEvent {
+            content: key.to_string(),
+            coalesce: Some(TextPopupCoalesce::Key(key.to_string())),
+            ..default()
+        }
+    }
+
+    fn live_popups(app: &mut App) -> Vec<TextPopupCoalesced> {
+        app.world_mut()
+            .query::<&TextPopupCoalesced>()
+            .iter(app.world())
+            .cloned()
+            .collect()
+    }
+
+    fn spawn_popup(app: &mut App, text_popup_event: TextPopupEvent) -> Entity {
+        let popup = app
+            .world_mut()
+            .commands()
+            .spawn_text_popup(text_popup_event)
+            .id();
+        app.update();
+        popup
+    }
+
+    fn close_reasons(app: &mut App) -> Vec<TextPopupCloseReason> {
+        app.world_mut()
+            .resource_mut::<Messages<TextPopupClosed>>()
+            .drain()
+            .map(|closed| closed.reason)
+            .collect()
+    }
+
+    fn entity(index: u32) -> Entity {
+        Entity::from_raw_u32(index).unwrap()
+    }
+
+    #[test]
+    fn pressing_a_button_reports_it_as_the_close_reason() {
+        let text_popup_event = || TextPopupEvent {
+            confirm_button: Some(default()),
+            buttons: vec![TextPopupButton {
+                keys: Some(vec![KeyCode::KeyB]),
+                ..default()
+            }],
+            dismiss_button: Some(default()),
+            ..default()
+        };
+        for (key_code, logical_key, reason) in [
+            (KeyCode::Enter, Key::Enter, TextPopupCloseReason::Confirmed),
+            (
+                KeyCode::Escape,
+                Key::Escape,
+                TextPopupCloseReason::Dismissed,
+            ),
+            (
+                KeyCode::KeyB,
+                Key::Character("b".into()),
+                TextPopupCloseReason::Button(1),
+            ),
+        ] {
+            let mut app = app();
+            spawn_popup(&mut app, text_popup_event());
+
+            press(&mut app, key_code, logical_key);
+
+            assert_eq!(close_reasons(&mut app), [reason]);
+        }
+    }
+
+    #[test]
+    fn frame_timeout_reports_the_popup_as_frame_expired() {
+        let mut app = app();
+        spawn_popup(
+            &mut app,
+            TextPopupEvent {
+                timeout: TextPopupTimeout::Frames(1),
+                ..default()
+            },
+        );
+        app.update();
+
+        assert_eq!(
+            close_reasons(&mut app),
+            [TextPopupCloseReason::FrameExpired]
+        );
+    }
+
+    #[test]
+    fn duration_timeout_reports_the_popup_as_timed_out() {
+        let mut app = app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+        spawn_popup(
+            &mut app,
+            TextPopupEvent {
+                timeout: TextPopupTimeout::Duration(Duration::from_millis(150)),
+                ..default()
+            },
+        );
+        for _ in 0..3 {
+            app.update();
+        }
+
+        assert_eq!(close_reasons(&mut app), [TextPopupCloseReason::TimedOut]);
+    }
+
+    #[test]
+    fn despawning_a_popup_reports_it_as_despawned() {
+        let mut app = app();
+        let popup = spawn_popup(&mut app, TextPopupEvent::default());
+
+        app.world_mut().commands().entity(popup).despawn();
+        app.update();
+
+        assert_eq!(close_reasons(&mut app), [TextPopupCloseReason::Despawned]);
+    }
+
+    #[test]
+    fn despawning_after_a_button_that_keeps_the_popup_open_reports_it_as_despawned() {
+        let mut app = app();
+        let popup = spawn_popup(
+            &mut app,
+            TextPopupEvent {
+                confirm_button: Some(TextPopupButton {
+                    action: TextPopupAction::new(|_, _| {}),
+                    ..default()
+                }),
+                ..default()
+            },
+        );
+
+        press(&mut app, KeyCode::Enter, Key::Enter);
+        assert!(close_reasons(&mut app).is_empty());
+
+        app.world_mut().commands().entity(popup).despawn();
+        app.update();
+
+        assert_eq!(close_reasons(&mut app), [TextPopupCloseReason::Despawned]);
+    }
+
+    #[test]
+    fn prompt_resolves_to_how_the_popup_closed() {
+        let mut app = app();
+        let prompt = app
+            .world_mut()
+            .commands()
+            .prompt_text_popup(TextPopupEvent {
+                confirm_button: Some(TextPopupButton {
+                    tag: Some("yes".into()),
+                    ..default()
+                }),
+                ..default()
+            });
+        app.update();
+        assert_eq!(prompt.result(), None);
+
+        press(&mut app, KeyCode::Enter, Key::Enter);
+        assert_eq!(
+            prompt.result(),
+            Some(TextPopupPromptResult::Button {
+                index: 0,
+                tag: Some("yes".into()),
+            })
+        );
+
+        let prompt = app
+            .world_mut()
+            .commands()
+            .prompt_text_popup(TextPopupEvent {
+                close_button: Some(default()),
+                ..default()
+            });
+        app.update();
+        let close_button = app
+            .world_mut()
+            .query_filtered::<Entity, With<TextPopupCloseButtonNode>>()
+            .single(app.world())
+            .unwrap();
+        app.world_mut()
+            .entity_mut(close_button)
+            .insert(Interaction::Pressed);
+        app.update();
+        assert_eq!(prompt.result(), Some(TextPopupPromptResult::Dismissed));
+
+        let prompt = app
+            .world_mut()
+            .commands()
+            .prompt_text_popup(TextPopupEvent {
+                timeout: TextPopupTimeout::Frames(1),
+                ..default()
+            });
+        app.update();
+        app.update();
+        assert_eq!(prompt.result(), Some(TextPopupPromptResult::TimedOut));
+    }
+
+    #[test]
+    fn evicted_prompt_resolves_as_dismissed() {
+        let mut app = app_with_capacity(
+            TextPopupCapacity::new(TextPopupCapacityPolicy::DropOldest).with_global_limit(1),
+        );
+        let prompt = app
+            .world_mut()
+            .commands()
+            .prompt_text_popup(TextPopupEvent::default());
+        app.update();
+
+        app.world_mut().write_message(TextPopupEvent::default());
+        app.update();
+
+        assert_eq!(prompt.result(), Some(TextPopupPromptResult::Dismissed));
+    }
+
+    #[test]
+    fn only_confirming_reports_the_selection() {
+        let text_popup_event = || TextPopupEvent {
+            choices: Some(TextPopupChoices {
+                options: vec!["Easy".into(), "Hard".into()],
+                selected: vec![1],
+                ..default()
+            }),
+            confirm_button: Some(TextPopupButton {
+                keys: Some(vec![KeyCode::KeyY]),
+                ..default()
+            }),
+            dismiss_button: Some(default()),
+            ..default()
+        };
+        let mut app = app();
+        spawn_popup(&mut app, text_popup_event());
+
+        press(&mut app, KeyCode::Escape, Key::Escape);
+
+        assert_eq!(close_reasons(&mut app), [TextPopupCloseReason::Dismissed]);
+        assert!(app
+            .world_mut()
+            .resource_mut::<Messages<TextPopupSelected>>()
+            .drain()
+            .next()
+            .is_none());
+
+        spawn_popup(&mut app, text_popup_event());
+
+        press(&mut app, KeyCode::KeyY, Key::Character("y".into()));
+
+        let selected: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Messages<TextPopupSelected>>()
+            .drain()
+            .map(|selected| selected.selected)
+            .collect();
+        assert_eq!(selected, [vec![1]]);
+    }
+
+    #[test]
+    fn make_room_evicts_the_oldest_popups_at_a_full_location() {
+        let text_popup_capacity = TextPopupCapacity::new(TextPopupCapacityPolicy::DropOldest)
+            .with_location_limit(TextPopupLocation::Top, 2);
+        let a = entity(1);
+        let b = entity(2);
+        let c = entity(3);
+        let mut live = vec![
+            (a, TextPopupLocation::Top),
+            (b, TextPopupLocation::Bottom),
+            (c, TextPopupLocation::Top),
+        ];
+
+        let evicted = make_room(&text_popup_capacity, &mut live, TextPopupLocation::Top);
+        assert_eq!(evicted, Some(vec![a]));
+        assert_eq!(
+            live,
+            [(b, TextPopupLocation::Bottom), (c, TextPopupLocation::Top)]
+        );
+
+        let evicted = make_room(&text_popup_capacity, &mut live, TextPopupLocation::Bottom);
+        assert_eq!(evicted, Some(Vec::new()));
+    }
+
+    #[test]
+    fn make_room_evicts_the_oldest_popups_overall_at_the_global_limit() {
+        let text_popup_capacity =
+            TextPopupCapacity::new(TextPopupCapacityPolicy::DropOldest).with_global_limit(1);
+        let a = entity(1);
+        let b = entity(2);
+        let mut live = vec![(a, TextPopupLocation::Top), (b, TextPopupLocation::Bottom)];
+
+        let evicted = make_room(&text_popup_capacity, &mut live, TextPopupLocation::Center);
+        assert_eq!(evicted, Some(vec![a, b]));
+        assert!(live.is_empty());
+    }
+
+    #[test]
+    fn make_room_keeps_live_popups_when_not_dropping_the_oldest() {
+        for policy in [
+            TextPopupCapacityPolicy::DropNewest,
+            TextPopupCapacityPolicy::Queue,
+        ] {
+            let text_popup_capacity = TextPopupCapacity::new(policy).with_global_limit(1);
+            let a = entity(1);
+            let mut live = vec![(a, TextPopupLocation::Top)];
+
+            assert_eq!(
+                make_room(&text_popup_capacity, &mut live, TextPopupLocation::Top),
+                None
+            );
+            assert_eq!(live, [(a, TextPopupLocation::Top)]);
+        }
+    }
+
+    #[test]
+    fn duplicates_in_one_frame_at_capacity_one_are_merged() {
+        let mut app = app_with_capacity(
+            TextPopupCapacity::new(TextPopupCapacityPolicy::DropOldest).with_global_limit(1),
+        );
+        app.world_mut().write_message(coalesced_popup("full"));
+        app.world_mut().write_message(coalesced_popup("full"));
+        app.update();
+
+        let popups = live_popups(&mut app);
+        assert_eq!(popups.len(), 1);
+        assert_eq!(popups[0].count, 2);
+    }
+
+    #[test]
+    fn duplicate_of_a_popup_evicted_in_the_same_frame_spawns_anew() {
+        let mut app = app_with_capacity(
+            TextPopupCapacity::new(TextPopupCapacityPolicy::DropOldest).with_global_limit(1),
+        );
+        app.world_mut().write_message(coalesced_popup("first"));
+        app.update();
+        // "second" evicts "first" before the duplicate of "first" is handled.
+        app.world_mut().write_message(coalesced_popup("second"));
+        app.world_mut().write_message(coalesced_popup("first"));
+        app.update();
+
+        let popups = live_popups(&mut app);
+        assert_eq!(popups.len(), 1);
+        assert_eq!(popups[0].key, "first");
+        assert_eq!(popups[0].count, 1);
+    }
+
+    #[test]
+    fn duplicate_of_a_popup_expiring_this_frame_spawns_anew() {
+        let mut app = app();
+        app.world_mut().write_message(TextPopupEvent {
+            timeout: TextPopupTimeout::Frames(1),
+            ..coalesced_popup("expiring")
+        });
+        app.update();
+        // The original expires in the same frame the duplicate is handled.
+        app.world_mut().write_message(coalesced_popup("expiring"));
+        app.update();
+
+        let popups = live_popups(&mut app);
+        assert_eq!(popups.len(), 1);
+        assert_eq!(popups[0].count, 1);
+    }
+
+    #[test]
+    fn popups_expiring_this_frame_do_not_count_toward_capacity() {
+        let expiring = |content: &str| TextPopupEvent {
+            timeout: TextPopupTimeout::Frames(1),
+            ..coalesced_popup(content)
+        };
+
+        let mut app = app_with_capacity(
+            TextPopupCapacity::new(TextPopupCapacityPolicy::DropOldest).with_global_limit(2),
+        );
+        app.world_mut().write_message(coalesced_popup("kept"));
+        app.world_mut().write_message(expiring("expiring"));
+        app.update();
+        app.world_mut().write_message(coalesced_popup("new"));
+        app.update();
+
+        let mut keys: Vec<_> = live_popups(&mut app)
+            .into_iter()
+            .map(|coalesced| coalesced.key)
+            .collect();
+        keys.sort();
+        assert_eq!(keys, ["kept", "new"]);
+
+        let mut app = app_with_capacity(
+            TextPopupCapacity::new(TextPopupCapacityPolicy::DropNewest).with_global_limit(1),
+        );
+        app.world_mut().write_message(expiring("expiring"));
+        app.update();
+        app.world_mut().write_message(coalesced_popup("new"));
+        app.update();
+
+        let keys: Vec<_> = live_popups(&mut app)
+            .into_iter()
+            .map(|coalesced| coalesced.key)
+            .collect();
+        assert_eq!(keys, ["new"]);
+    }
+
+    #[test]
+    fn keys_used_by_a_focused_popup_do_not_dismiss_it() {
+        let mut app = app();
+        // Only the popup without a text field is dismissed by the key press.
+        app.world_mut().write_message(TextPopupEvent {
+            input: Some(TextPopupInput::default()),
+            dismiss_on: TextPopupDismissTrigger::AnyKey,
+            ..default()
+        });
+        app.world_mut().write_message(TextPopupEvent {
+            dismiss_on: TextPopupDismissTrigger::AnyKey,
+            ..default()
+        });
+        app.update();
+
+        press(&mut app, KeyCode::KeyA, Key::Character("a".into()));
+
+        let values: Vec<_> = app
+            .world_mut()
+            .query_filtered::<&TextPopupInputState, With<TextPopup>>()
+            .iter(app.world())
+            .map(|state| state.value().to_string())
+            .collect();
+        assert_eq!(values, ["a"]);
+        assert_eq!(
+            app.world_mut()
+                .query::<&TextPopup>()
+                .iter(app.world())
+                .count(),
+            1
+        );
+
+        // Moving between the buttons of a popup doesn't dismiss it either, but focus leaving the
+        // text field lets the first popup be dismissed.
+        app.world_mut().write_message(TextPopupEvent {
+            confirm_button: Some(default()),
+            dismiss_button: Some(default()),
+            default_focus: Some(0),
+            dismiss_on: TextPopupDismissTrigger::AnyKey,
+            ..default()
+        });
+        app.update();
+
+        press(&mut app, KeyCode::ArrowRight, Key::ArrowRight);
+
+        let remaining: Vec<_> = app
+            .world_mut()
+            .query_filtered::<Has<TextPopupInputState>, With<TextPopup>>()
+            .iter(app.world())
+            .collect();
+        assert_eq!(remaining, [false]);
+    }
+
+    #[test]
+    fn other_keys_dismiss_a_popup_with_a_focused_button() {
+        let mut app = app();
+        app.world_mut().write_message(TextPopupEvent {
+            confirm_button: Some(default()),
+            dismiss_button: Some(default()),
+            default_focus: Some(0),
+            dismiss_on: TextPopupDismissTrigger::AnyKey,
+            ..default()
+        });
+        app.update();
+
+        press(&mut app, KeyCode::KeyA, Key::Character("a".into()));
+
+        assert_eq!(
+            app.world_mut()
+                .query::<&TextPopup>()
+                .iter(app.world())
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn ticking_dont_show_again_does_not_dismiss_the_popup() {
+        let mut app = app();
+        spawn_popup(
+            &mut app,
+            TextPopupEvent {
+                suppress_key: Some("hint".into()),
+                dismiss_on: TextPopupDismissTrigger::AnyInput,
+                ..default()
+            },
+        );
+
+        let checkbox = app
+            .world_mut()
+            .query_filtered::<Entity, With<TextPopupSuppressCheckbox>>()
+            .single(app.world())
+            .unwrap();
+        app.world_mut()
+            .entity_mut(checkbox)
+            .insert(Interaction::Pressed);
+        app.world_mut().write_message(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+
+        assert!(app
+            .world()
+            .resource::<TextPopupSuppression>()
+            .is_suppressed("hint"));
+        assert_eq!(
+            app.world_mut()
+                .query::<&TextPopup>()
+                .iter(app.world())
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn focused_popup_expiring_does_not_panic() {
+        let mut app = app();
+        app.world_mut().write_message(TextPopupEvent {
+            confirm_button: Some(default()),
+            default_focus: Some(0),
+            timeout: TextPopupTimeout::Frames(1),
+            ..default()
+        });
+        app.update();
+        app.update();
+
+        assert_eq!(
+            close_reasons(&mut app),
+            [TextPopupCloseReason::FrameExpired]
+        );
+    }
+
+    #[test]
+    fn hovering_a_popup_as_it_expires_does_not_panic() {
+        let mut app = app();
+        let popup = spawn_popup(
+            &mut app,
+            TextPopupEvent {
+                pause_on_hover: true,
+                timeout: TextPopupTimeout::Frames(2),
+                ..default()
+            },
+        );
+
+        // The pointer enters the popup in its last frame.
+        let panel = app.world().get::<TextPopupNodes>(popup).unwrap().panel;
+        app.world_mut()
+            .get_mut::<RelativeCursorPosition>(panel)
+            .unwrap()
+            .cursor_over = true;
+        app.update();
+
+        assert_eq!(
+            close_reasons(&mut app),
+            [TextPopupCloseReason::FrameExpired]
+        );
+    }
+
+    #[test]
+    fn shift_enter_submits_a_single_line_input() {
+        let mut app = app();
+        app.world_mut().write_message(TextPopupEvent {
+            input: Some(TextPopupInput::default()),
+            ..default()
+        });
+        app.update();
+
+        press(&mut app, KeyCode::KeyA, Key::Character("a".into()));
+        press(&mut app, KeyCode::ShiftLeft, Key::Shift);
+        press(&mut app, KeyCode::Enter, Key::Enter);
+
+        let submitted: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Messages<TextPopupSubmitted>>()
+            .drain()
+            .map(|submitted| submitted.value)
+            .collect();
+        assert_eq!(submitted, ["a"]);
+        assert_eq!(
+            app.world_mut()
+                .query::<&TextPopup>()
+                .iter(app.world())
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn enter_does_not_close_a_popup_whose_confirm_button_is_disabled() {
+        let mut app = app();
+        app.world_mut().write_message(TextPopupEvent {
+            input: Some(TextPopupInput::default()),
+            confirm_button: Some(TextPopupButton {
+                enabled: TextPopupButtonEnabled::Never,
+                ..default()
+            }),
+            ..default()
+        });
+        app.update();
+
+        press(&mut app, KeyCode::KeyA, Key::Character("a".into()));
+        press(&mut app, KeyCode::Enter, Key::Enter);
+
+        assert!(app
+            .world_mut()
+            .resource_mut::<Messages<TextPopupSubmitted>>()
+            .drain()
+            .next()
+            .is_none());
+        assert_eq!(
+            app.world_mut()
+                .query::<&TextPopup>()
+                .iter(app.world())
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn wizard_message_without_pages_shows_a_plain_popup() {
+        let mut app = app();
+        app.world_mut().write_message(TextPopupEvent {
+            wizard: Some(TextPopupWizard::default()),
+            ..default()
+        });
+        app.update();
+
+        let wizards: Vec<_> = app
+            .world_mut()
+            .query_filtered::<Has<TextPopupWizardState>, With<TextPopup>>()
+            .iter(app.world())
+            .collect();
+        assert_eq!(wizards, [false]);
+    }
+
+    #[test]
+    fn spawning_a_wizard_without_pages_shows_a_plain_popup() {
+        let mut app = app();
+        app.world_mut().commands().spawn_text_popup(TextPopupEvent {
+            wizard: Some(TextPopupWizard::default()),
+            ..default()
+        });
+        app.update();
+
+        let wizards: Vec<_> = app
+            .world_mut()
+            .query_filtered::<Has<TextPopupWizardState>, With<TextPopup>>()
+            .iter(app.world())
+            .collect();
+        assert_eq!(wizards, [false]);
+    }
+}
@@ -1,53 +1,117 @@
+use std::{any::TypeId, time::Duration};
+
 use bevy::{
-    prelude::{Button, Commands, Node, Text},
-    text::{Justify, TextLayout},
-    time::Time,
-    ui::{AlignItems, Display, FlexDirection, GlobalZIndex, JustifyContent, PositionType, Val},
+    input_focus::AutoFocus,
+    log::warn,
+    prelude::{
+        Button, ChildSpawnerCommands, Commands, Entity, EntityCommands, EntityWorldMut, KeyCode,
+        Node, Text,
+    },
+    text::{Justify, TextFont, TextLayout},
+    ui::{
+        AlignItems, BackgroundColor, BorderColor, Display, FlexDirection, FocusPolicy,
+        GlobalZIndex, Interaction, InteractionDisabled, JustifyContent, Outline, PositionType,
+        RelativeCursorPosition, UiRect, Val,
+    },
 };
 
 use crate::{
-    TextPopup, TextPopupActionNode, TextPopupButtonActionData, TextPopupEvent, TextPopupExpires,
-    TextPopupExpiresInFrames, TextPopupLocation, TextPopupNeverExpires, TextPopupRootNode,
-    TextPopupTextNode, TextPopupTimeout,
+    TextPopup, TextPopupAction, TextPopupActionNode, TextPopupButton, TextPopupButtonActionData,
+    TextPopupButtonEnabled, TextPopupButtonStyle, TextPopupButtonStyles, TextPopupChoice,
+    TextPopupChoiceState, TextPopupChoices, TextPopupClock, TextPopupCloseButton,
+    TextPopupCloseButtonNode, TextPopupCloseReason, TextPopupCoalesce, TextPopupCoalesced,
+    TextPopupDismissTrigger, TextPopupEvent, TextPopupExpires, TextPopupExpiresInFrames,
+    TextPopupInput, TextPopupInputField, TextPopupInputState, TextPopupLocation,
+    TextPopupNeverExpires, TextPopupNodes, TextPopupPauseOnHover, TextPopupRootNode,
+    TextPopupSelection, TextPopupSpawnCounter, TextPopupSpawned, TextPopupSuppressCheckbox,
+    TextPopupTextNode, TextPopupTimeout, TextPopupUsesClock, TextPopupWizard, TextPopupWizardState,
 };
 
 pub fn generate_text_popup_from_event(
     commands: &mut Commands,
-    time: &Time,
     text_popup_event: &TextPopupEvent,
-) {
+) -> Entity {
+    // A wizard without any pages shows a plain popup with its `content` instead.
+    if text_popup_event
+        .wizard
+        .as_ref()
+        .is_some_and(|wizard| wizard.pages.is_empty())
+    {
+        warn!("Showing a text popup wizard without any pages as a plain popup");
+        let text_popup_event = TextPopupEvent {
+            wizard: None,
+            ..text_popup_event.clone()
+        };
+        return generate_text_popup_from_event(commands, &text_popup_event);
+    }
     let root_node = get_root_node(text_popup_event);
     let text_node = get_text_node(text_popup_event);
-    let action_node = get_action_node();
+    let action_node = get_action_node(text_popup_event);
     spawn_text_popup(
         commands,
-        time,
         text_popup_event,
         root_node,
         text_node,
         action_node,
         text_popup_event.z_index,
-    );
+    )
 }
 
-fn get_root_node(text_popup_event: &TextPopupEvent) -> TextPopupRootNode {
-    let justify_content = match text_popup_event.location {
-        TextPopupLocation::TopLeft | TextPopupLocation::Left | TextPopupLocation::BottomLeft => {
-            JustifyContent::Start
+/// The key used to find duplicates of a popup sent with [`TextPopupEvent::coalesce`].
+pub fn coalesce_key(text_popup_event: &TextPopupEvent) -> Option<String> {
+    match text_popup_event.coalesce.as_ref()? {
+        TextPopupCoalesce::Content => Some(text_popup_event.content.clone()),
+        TextPopupCoalesce::Key(key) => Some(key.clone()),
+    }
+}
+
+/// Starts the given timeout on a popup root, replacing any previous one.
+pub fn insert_timeout(
+    entity_commands: &mut EntityCommands,
+    timeout: &TextPopupTimeout,
+    clock: TextPopupClock,
+) {
+    entity_commands.try_remove::<(
+        TextPopupExpires,
+        TextPopupUsesClock,
+        TextPopupExpiresInFrames,
+        TextPopupNeverExpires,
+    )>();
+    match *timeout {
+        TextPopupTimeout::Seconds(seconds) => {
+            clock.insert_expiration(entity_commands, Duration::from_secs(seconds.into()));
         },
-        TextPopupLocation::Top | TextPopupLocation::Center | TextPopupLocation::Bottom => {
-            JustifyContent::Center
+        TextPopupTimeout::Duration(duration) => {
+            clock.insert_expiration(entity_commands, duration);
         },
-        TextPopupLocation::TopRight | TextPopupLocation::Right | TextPopupLocation::BottomRight => {
-            JustifyContent::End
+        TextPopupTimeout::Frames(frames) => {
+            entity_commands.try_insert(TextPopupExpiresInFrames {
+                frames_remaining: frames,
+            });
         },
-        TextPopupLocation::Custom(_, _) => JustifyContent::Start,
-    };
+        TextPopupTimeout::Never => {
+            entity_commands.try_insert(TextPopupNeverExpires);
+        },
+    }
+}
+
+/// Expires the popup after `duration` as measured by `Time<T>`.
+pub fn insert_expiration<T: Default + Send + Sync + 'static>(
+    entity_commands: &mut EntityCommands,
+    duration: Duration,
+) {
+    entity_commands.try_insert((
+        TextPopupExpires::new(duration),
+        TextPopupUsesClock(TypeId::of::<T>()),
+    ));
+}
+
+fn get_root_node(text_popup_event: &TextPopupEvent) -> TextPopupRootNode {
     let node = Node {
         position_type: PositionType::Absolute,
         display: Display::Flex,
         flex_direction: FlexDirection::Row,
-        justify_content,
+        justify_content: get_root_justify_content(text_popup_event.location),
         align_items: AlignItems::Center,
         width: Val::Percent(100.),
         height: Val::Percent(100.),
@@ -70,7 +134,53 @@ fn get_text_node(text_popup_event: &TextPopupEvent) -> TextPopupTextNode {
         justify_content: JustifyContent::Center,
         ..Default::default()
     };
-    match text_popup_event.location {
+    set_text_node_location(&mut node, text_popup_event.location);
+    TextPopupTextNode {
+        node,
+        border_color: text_popup_event.border_color,
+        background_color: text_popup_event.background_color,
+        text: Text::new(
+            text_popup_event
+                .wizard
+                .as_ref()
+                .and_then(|wizard| wizard.pages.first())
+                .map_or_else(
+                    || text_popup_event.content.clone(),
+                    |page| page.content.clone(),
+                ),
+        ),
+        text_layout: TextLayout {
+            justify: text_popup_event.text_alignment,
+            ..Default::default()
+        },
+        text_font: text_popup_event.text_font.clone(),
+        text_color: text_popup_event.text_color,
+    }
+}
+
+/// Where the full-screen root node places the text node along the horizontal axis.
+pub fn get_root_justify_content(location: TextPopupLocation) -> JustifyContent {
+    match location {
+        TextPopupLocation::TopLeft | TextPopupLocation::Left | TextPopupLocation::BottomLeft => {
+            JustifyContent::Start
+        },
+        TextPopupLocation::Top | TextPopupLocation::Center | TextPopupLocation::Bottom => {
+            JustifyContent::Center
+        },
+        TextPopupLocation::TopRight | TextPopupLocation::Right | TextPopupLocation::BottomRight => {
+            JustifyContent::End
+        },
+        TextPopupLocation::Custom(_, _) => JustifyContent::Start,
+    }
+}
+
+/// Pins the text node to the screen edges matching `location`.
+pub fn set_text_node_location(node: &mut Node, location: TextPopupLocation) {
+    node.top = Val::Auto;
+    node.left = Val::Auto;
+    node.right = Val::Auto;
+    node.bottom = Val::Auto;
+    match location {
         TextPopupLocation::TopLeft | TextPopupLocation::Top | TextPopupLocation::TopRight => {
             node.top = Val::Percent(0.);
         },
@@ -87,134 +197,629 @@ fn get_text_node(text_popup_event: &TextPopupEvent) -> TextPopupTextNode {
             node.top = Val::Px(y);
         },
     };
-    TextPopupTextNode {
-        node,
-        border_color: text_popup_event.border_color,
-        background_color: text_popup_event.background_color,
-        text: Text::new(text_popup_event.content.clone()),
-        text_layout: TextLayout {
-            justify: text_popup_event.text_alignment,
-            ..Default::default()
-        },
-        text_font: text_popup_event.text_font.clone(),
-        text_color: text_popup_event.text_color,
-    }
 }
 
-fn get_action_node() -> TextPopupActionNode {
+fn get_action_node(text_popup_event: &TextPopupEvent) -> TextPopupActionNode {
     TextPopupActionNode(Node {
         padding: bevy::ui::UiRect::top(Val::Px(5.)),
+        flex_direction: text_popup_event.action_direction,
         ..Default::default()
     })
 }
 
+/// The popup's buttons in action row order, with the reason reported if they close the popup.
+fn get_buttons(text_popup_event: &TextPopupEvent) -> Vec<(TextPopupButton, TextPopupCloseReason)> {
+    let mut buttons = Vec::new();
+    if let Some(wizard) = &text_popup_event.wizard {
+        buttons.extend(get_wizard_buttons(wizard));
+    } else {
+        if let Some(confirm_button) = &text_popup_event.confirm_button {
+            buttons.push((confirm_button.clone(), TextPopupCloseReason::Confirmed));
+        }
+        for button in &text_popup_event.buttons {
+            buttons.push((button.clone(), TextPopupCloseReason::Button(buttons.len())));
+        }
+    }
+    if let Some(dismiss_button) = &text_popup_event.dismiss_button {
+        buttons.push((dismiss_button.clone(), TextPopupCloseReason::Dismissed));
+    }
+    buttons
+}
+
+/// The Back, Next and Finish buttons of a wizard, in that order.
+fn get_wizard_buttons(wizard: &TextPopupWizard) -> [(TextPopupButton, TextPopupCloseReason); 3] {
+    [
+        (
+            TextPopupButton {
+                action: TextPopupAction::new(|commands, root_id| {
+                    commands
+                        .entity(root_id)
+                        .queue_silenced(turn_wizard_page(false));
+                }),
+                ..wizard.back_button.clone()
+            },
+            TextPopupCloseReason::Button(0),
+        ),
+        (
+            TextPopupButton {
+                action: TextPopupAction::new(|commands, root_id| {
+                    commands
+                        .entity(root_id)
+                        .queue_silenced(turn_wizard_page(true));
+                }),
+                ..wizard.next_button.clone()
+            },
+            TextPopupCloseReason::Button(1),
+        ),
+        (
+            TextPopupButton {
+                action: TextPopupAction::new(|commands, root_id| {
+                    commands.entity(root_id).queue_silenced(finish_wizard);
+                }),
+                ..wizard.finish_button.clone()
+            },
+            TextPopupCloseReason::Confirmed,
+        ),
+    ]
+}
+
+/// Which of the Back, Next and Finish buttons are shown on a wizard page.
+pub fn visible_wizard_buttons(page: usize, page_count: usize) -> [bool; 3] {
+    let last_page = page + 1 >= page_count;
+    [page > 0, !last_page, last_page]
+}
+
+/// Runs the validation of the page being shown, if it has one.
+fn validate_wizard_page(entity: &mut EntityWorldMut) -> bool {
+    let root_id = entity.id();
+    let Some(validate) = entity
+        .get::<TextPopupWizardState>()
+        .and_then(|state| state.pages.get(state.page)?.validate)
+    else {
+        return true;
+    };
+    let valid = entity
+        .world_scope(|world| world.run_system_with(validate, root_id))
+        .unwrap_or(false);
+    valid && !entity.is_despawned()
+}
+
+fn turn_wizard_page(forward: bool) -> impl FnOnce(EntityWorldMut) + Send + 'static {
+    move |mut entity: EntityWorldMut| {
+        if forward && !validate_wizard_page(&mut entity) {
+            return;
+        }
+        if let Some(mut state) = entity.get_mut::<TextPopupWizardState>() {
+            let page = if forward {
+                state.page + 1
+            } else {
+                state.page.saturating_sub(1)
+            };
+            state.set_page(page);
+        }
+    }
+}
+
+fn finish_wizard(mut entity: EntityWorldMut) {
+    if validate_wizard_page(&mut entity) {
+        entity.despawn();
+    }
+}
+
+fn spawn_input_field(
+    commands: &mut ChildSpawnerCommands,
+    root_id: Entity,
+    text_popup_event: &TextPopupEvent,
+    input: &TextPopupInput,
+) -> TextPopupInputState {
+    let text_font = input
+        .text_font
+        .clone()
+        .unwrap_or_else(|| text_popup_event.text_font.clone());
+    let text_color = input.text_color.unwrap_or(text_popup_event.text_color);
+    let field = commands
+        .spawn((
+            Node {
+                min_width: input.min_width,
+                // Text areas have a fixed width so that long lines wrap.
+                width: if input.multiline {
+                    input.min_width
+                } else {
+                    Val::Auto
+                },
+                border: input.border,
+                padding: input.padding,
+                margin: input.margin,
+                ..Default::default()
+            },
+            input.background_color,
+            input.border_color,
+            Text::default(),
+            text_font.clone(),
+            text_color,
+            Interaction::default(),
+            // Keeps clicks on the field from counting as clicks on the popup body.
+            FocusPolicy::Block,
+            TextPopupInputField { root_id },
+            AutoFocus,
+        ))
+        .id();
+    let character_count = input.show_character_count.then(|| {
+        commands
+            .spawn((
+                Text::default(),
+                TextFont {
+                    font_size: text_font.font_size * 0.75,
+                    ..text_font
+                },
+                input.placeholder_color,
+            ))
+            .id()
+    });
+    let mut state = TextPopupInputState {
+        value: String::new(),
+        caret: 0,
+        field,
+        character_count,
+        text_color,
+        input: input.clone(),
+    };
+    state.set_value(input.initial_value.clone());
+    state
+}
+
+fn spawn_choices(
+    commands: &mut ChildSpawnerCommands,
+    root_id: Entity,
+    text_popup_event: &TextPopupEvent,
+    choices: &TextPopupChoices,
+) -> TextPopupChoiceState {
+    let text_font = choices
+        .text_font
+        .clone()
+        .unwrap_or_else(|| text_popup_event.text_font.clone());
+    let text_color = choices.text_color.unwrap_or(text_popup_event.text_color);
+    let mut selected: Vec<_> = choices
+        .selected
+        .iter()
+        .copied()
+        .filter(|index| *index < choices.options.len())
+        .collect();
+    selected.sort_unstable();
+    selected.dedup();
+    if choices.selection == TextPopupSelection::Single {
+        selected.truncate(1);
+    }
+    let mut state = TextPopupChoiceState {
+        selected,
+        texts: Vec::new(),
+        choices: choices.clone(),
+    };
+    commands
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexStart,
+            margin: choices.margin,
+            ..Default::default()
+        })
+        .with_children(|commands| {
+            for index in 0..choices.options.len() {
+                let mut option = commands.spawn((
+                    Button,
+                    Node {
+                        padding: choices.padding,
+                        ..Default::default()
+                    },
+                    TextPopupChoice { root_id, index },
+                ));
+                // Without a text field to type into, the list takes the focus.
+                if index == 0 && text_popup_event.input.is_none() {
+                    option.insert(AutoFocus);
+                }
+                let mut text = Entity::PLACEHOLDER;
+                option.with_children(|commands| {
+                    text = commands
+                        .spawn((
+                            Text::new(state.label(index)),
+                            text_font.clone(),
+                            text_color,
+                            BackgroundColor::default(),
+                        ))
+                        .id();
+                });
+                option.insert(TextPopupButtonStyles {
+                    text,
+                    normal: TextPopupButtonStyle {
+                        background_color: Some(BackgroundColor::default()),
+                        border_color: Some(BorderColor::default()),
+                        text_color: Some(text_color),
+                        scale: Some(1.),
+                    },
+                    hovered: choices.hovered.clone(),
+                    pressed: TextPopupButtonStyle::default(),
+                    disabled: TextPopupButtonStyle::default(),
+                    focus_outline: choices.focus_outline,
+                });
+                state.texts.push(text);
+            }
+        });
+    state
+}
+
+fn spawn_suppress_checkbox(
+    commands: &mut ChildSpawnerCommands,
+    root_id: Entity,
+    text_popup_event: &TextPopupEvent,
+    suppress_key: &str,
+) {
+    let mut checkbox = commands.spawn((
+        Button,
+        Node {
+            margin: UiRect::all(Val::Px(5.)),
+            ..Default::default()
+        },
+    ));
+    let mut text = Entity::PLACEHOLDER;
+    checkbox.with_children(|commands| {
+        text = commands
+            .spawn((
+                Text::default(),
+                TextFont {
+                    font_size: text_popup_event.text_font.font_size * 0.75,
+                    ..text_popup_event.text_font.clone()
+                },
+                text_popup_event.text_color,
+            ))
+            .id();
+    });
+    checkbox.insert(TextPopupSuppressCheckbox {
+        root_id,
+        key: suppress_key.to_string(),
+        text,
+        checked: false,
+    });
+}
+
+fn spawn_close_button(
+    commands: &mut ChildSpawnerCommands,
+    root_id: Entity,
+    close_button: &TextPopupCloseButton,
+) {
+    let mut button_commands = commands.spawn((
+        Button,
+        Node {
+            position_type: PositionType::Absolute,
+            top: close_button.offset,
+            right: close_button.offset,
+            border: close_button.border,
+            padding: close_button.padding,
+            ..Default::default()
+        },
+        close_button.border_color,
+        TextPopupCloseButtonNode { root_id },
+    ));
+    let mut text = Entity::PLACEHOLDER;
+    button_commands.with_children(|commands| {
+        text = commands
+            .spawn((
+                Text::new(close_button.text.clone()),
+                close_button.text_font.clone(),
+                close_button.text_color,
+                close_button.background_color,
+            ))
+            .id();
+    });
+    button_commands.insert(TextPopupButtonStyles {
+        text,
+        normal: TextPopupButtonStyle {
+            background_color: Some(close_button.background_color),
+            border_color: Some(close_button.border_color),
+            text_color: Some(close_button.text_color),
+            scale: Some(1.),
+        },
+        hovered: close_button.hovered.clone(),
+        pressed: close_button.pressed.clone(),
+        disabled: TextPopupButtonStyle::default(),
+        focus_outline: Outline::default(),
+    });
+}
+
+fn default_keys(close_reason: TextPopupCloseReason) -> Vec<KeyCode> {
+    match close_reason {
+        TextPopupCloseReason::Confirmed => vec![KeyCode::Enter],
+        TextPopupCloseReason::Dismissed => vec![KeyCode::Escape],
+        _ => Vec::new(),
+    }
+}
+
+fn spawn_button<'a>(
+    commands: &'a mut ChildSpawnerCommands,
+    root_id: Entity,
+    index: usize,
+    button: &TextPopupButton,
+    close_reason: TextPopupCloseReason,
+) -> EntityCommands<'a> {
+    let mut button_commands = commands.spawn((
+        Button,
+        Node {
+            border: button.border,
+            padding: button.padding,
+            margin: button.margin,
+            ..Default::default()
+        },
+        button.border_color,
+        TextLayout::new_with_justify(Justify::Center),
+        TextPopupButtonActionData {
+            root_id,
+            index,
+            action: button.action.clone(),
+            tag: button.tag.clone(),
+            keys: button
+                .keys
+                .clone()
+                .unwrap_or_else(|| default_keys(close_reason)),
+            close_reason,
+        },
+    ));
+    let mut text = Entity::PLACEHOLDER;
+    button_commands.with_children(|commands| {
+        text = commands
+            .spawn((
+                Text::new(button.text.clone()),
+                button.text_font.clone(),
+                button.text_color,
+                button.background_color,
+            ))
+            .id();
+    });
+    button_commands.insert(TextPopupButtonStyles {
+        text,
+        normal: TextPopupButtonStyle {
+            background_color: Some(button.background_color),
+            border_color: Some(button.border_color),
+            text_color: Some(button.text_color),
+            scale: Some(1.),
+        },
+        hovered: button.hovered.clone(),
+        pressed: button.pressed.clone(),
+        disabled: button.disabled.clone(),
+        focus_outline: button.focus_outline,
+    });
+    match button.enabled {
+        TextPopupButtonEnabled::Always => {},
+        TextPopupButtonEnabled::Never => {
+            button_commands.insert((button.enabled, InteractionDisabled));
+        },
+        TextPopupButtonEnabled::When(_) => {
+            button_commands.insert(button.enabled);
+        },
+    }
+    button_commands
+}
+
 fn spawn_text_popup(
     commands: &mut Commands,
-    time: &Time,
     text_popup_event: &TextPopupEvent,
     root_node: TextPopupRootNode,
     text_node: TextPopupTextNode,
     action_node: TextPopupActionNode,
     z_index: GlobalZIndex,
-) {
+) -> Entity {
     let mut spawned_root = commands.spawn((
         TextPopup,
         root_node.node,
         root_node.background_color,
         z_index,
+        text_popup_event.location,
     ));
-    let spawned_root = match text_popup_event.timeout {
-        TextPopupTimeout::Seconds(seconds) => spawned_root.insert(TextPopupExpires {
-            expiration_time: time.elapsed_secs_f64() + seconds as f64,
-        }),
-        TextPopupTimeout::Frames(frames) => spawned_root.insert(TextPopupExpiresInFrames {
-            frames_remaining: frames,
-        }),
-        TextPopupTimeout::Never => spawned_root.insert(TextPopupNeverExpires),
-    };
+    // Numbered when the command is applied, which keeps the order popups were spawned in.
+    spawned_root.queue(|mut entity: EntityWorldMut| {
+        let spawn_order =
+            entity.world_scope(|world| world.resource_mut::<TextPopupSpawnCounter>().next());
+        entity.insert(spawn_order);
+    });
+    insert_timeout(
+        &mut spawned_root,
+        &text_popup_event.timeout,
+        text_popup_event.clock,
+    );
     if let Some(name) = &text_popup_event.name {
         spawned_root.insert(name.clone());
     }
     if let Some(custom_component_fn) = text_popup_event.custom_component {
-        custom_component_fn(spawned_root);
+        custom_component_fn(&mut spawned_root);
+    }
+    if let Some(key) = coalesce_key(text_popup_event) {
+        spawned_root.insert(TextPopupCoalesced {
+            key,
+            content: text_popup_event.content.clone(),
+            count: 1,
+        });
+    }
+    if text_popup_event.pause_on_hover {
+        spawned_root.insert(TextPopupPauseOnHover);
+    }
+    let dismiss_on = text_popup_event.dismiss_on;
+    let dismiss_on_click = matches!(
+        dismiss_on,
+        TextPopupDismissTrigger::ClickPopup | TextPopupDismissTrigger::ClickOutside
+    );
+    if dismiss_on != TextPopupDismissTrigger::Never {
+        spawned_root.insert(dismiss_on);
+    }
+    if dismiss_on_click {
+        spawned_root.insert(Interaction::default());
     }
     let root_id = spawned_root.id();
+    let mut nodes = TextPopupNodes {
+        panel: Entity::PLACEHOLDER,
+        text: Entity::PLACEHOLDER,
+        actions: Entity::PLACEHOLDER,
+    };
+    let mut input_state = None;
+    let mut choice_state = None;
+    let mut wizard_indicator = None;
+    let mut wizard_buttons = [Entity::PLACEHOLDER; 3];
     spawned_root.with_children(|commands| {
-        commands
-            .spawn((
-                text_node.node,
-                text_node.border_color,
-                text_node.background_color,
-            ))
-            .with_children(|commands| {
-                commands.spawn((
+        let mut panel = commands.spawn((
+            text_node.node,
+            text_node.border_color,
+            text_node.background_color,
+            RelativeCursorPosition::default(),
+        ));
+        if dismiss_on_click {
+            panel.insert(Interaction::default());
+        }
+        nodes.panel = panel.id();
+        panel.with_children(|commands| {
+            nodes.text = commands
+                .spawn((
                     text_node.text,
                     text_node.text_layout,
                     text_node.text_font,
                     text_node.text_color,
+                ))
+                .id();
+            if let Some(wizard) = &text_popup_event.wizard {
+                wizard_indicator = wizard.page_indicator.then(|| {
+                    commands
+                        .spawn((
+                            Text::new(format!("Page 1 of {}", wizard.pages.len())),
+                            TextFont {
+                                font_size: text_popup_event.text_font.font_size * 0.75,
+                                ..text_popup_event.text_font.clone()
+                            },
+                            text_popup_event.text_color,
+                        ))
+                        .id()
+                });
+            }
+            if let Some(input) = &text_popup_event.input {
+                input_state = Some(spawn_input_field(
+                    commands,
+                    root_id,
+                    text_popup_event,
+                    input,
                 ));
-                commands.spawn(action_node.0).with_children(|commands| {
-                    if let Some(confirm_button) = text_popup_event.confirm_button.clone() {
-                        commands
-                            .spawn((
-                                Button,
-                                Node {
-                                    border: confirm_button.border,
-                                    padding: confirm_button.padding,
-                                    margin: confirm_button.margin,
-                                    ..Default::default()
-                                },
-                                confirm_button.border_color,
-                                TextLayout::new_with_justify(Justify::Center),
-                                TextPopupButtonActionData {
-                                    root_id,
-                                    action: text_popup_event
-                                        .confirm_button
-                                        .as_ref()
-                                        .unwrap()
-                                        .action,
-                                },
-                            ))
-                            .with_children(|commands| {
-                                commands.spawn((
-                                    Text::new(confirm_button.text),
-                                    confirm_button.text_font,
-                                    confirm_button.text_color,
-                                    confirm_button.background_color,
-                                ));
-                            });
+            }
+            if let Some(choices) = &text_popup_event.choices {
+                choice_state = Some(spawn_choices(commands, root_id, text_popup_event, choices));
+            }
+            if let Some(suppress_key) = &text_popup_event.suppress_key {
+                spawn_suppress_checkbox(commands, root_id, text_popup_event, suppress_key);
+            }
+            if let Some(close_button) = &text_popup_event.close_button {
+                spawn_close_button(commands, root_id, close_button);
+            }
+            let mut actions = commands.spawn(action_node.0);
+            nodes.actions = actions.id();
+            actions.with_children(|commands| {
+                for (index, (button, close_reason)) in
+                    get_buttons(text_popup_event).into_iter().enumerate()
+                {
+                    let mut button = spawn_button(commands, root_id, index, &button, close_reason);
+                    if text_popup_event.default_focus == Some(index) {
+                        button.insert(AutoFocus);
                     }
-                    if let Some(dismiss_button) = text_popup_event.dismiss_button.clone() {
-                        commands
-                            .spawn((
-                                Button,
-                                Node {
-                                    border: dismiss_button.border,
-                                    padding: dismiss_button.padding,
-                                    margin: dismiss_button.margin,
-                                    ..Default::default()
-                                },
-                                dismiss_button.border_color,
-                                TextLayout::new_with_justify(Justify::Center),
-                                TextPopupButtonActionData {
-                                    root_id,
-                                    action: text_popup_event
-                                        .dismiss_button
-                                        .as_ref()
-                                        .unwrap()
-                                        .action,
-                                },
-                            ))
-                            .with_children(|commands| {
-                                commands.spawn((
-                                    Text::new(dismiss_button.text),
-                                    dismiss_button.text_font,
-                                    dismiss_button.text_color,
-                                    dismiss_button.background_color,
-                                ));
-                            });
+                    let Some(wizard) = &text_popup_event.wizard else {
+                        continue;
+                    };
+                    if index < wizard_buttons.len() {
+                        wizard_buttons[index] = button.id();
+                        if !visible_wizard_buttons(0, wizard.pages.len())[index] {
+                            button
+                                .entry::<Node>()
+                                .and_modify(|mut node| node.display = Display::None);
+                        }
                     }
-                });
+                }
             });
+        });
     });
+    spawned_root.insert(nodes);
+    if let Some(input_state) = input_state {
+        spawned_root.insert(input_state);
+    }
+    if let Some(choice_state) = choice_state {
+        spawned_root.insert(choice_state);
+    }
+    if let Some(wizard) = &text_popup_event.wizard {
+        let [back, next, finish] = wizard_buttons;
+        spawned_root.insert(TextPopupWizardState {
+            page: 0,
+            pages: wizard.pages.clone(),
+            back,
+            next,
+            finish,
+            indicator: wizard_indicator,
+        });
+    }
+    commands.write_message(TextPopupSpawned {
+        entity: root_id,
+        name: text_popup_event.name.clone(),
+    });
+    root_id
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::default;
+
+    use super::*;
+
+    #[test]
+    fn coalesce_key_follows_the_coalesce_mode() {
+        let mut text_popup_event = TextPopupEvent {
+            content: "Inventory full".to_string(),
+            ..default()
+        };
+        assert_eq!(coalesce_key(&text_popup_event), None);
+
+        text_popup_event.coalesce = Some(TextPopupCoalesce::Content);
+        assert_eq!(
+            coalesce_key(&text_popup_event).as_deref(),
+            Some("Inventory full")
+        );
+
+        text_popup_event.coalesce = Some(TextPopupCoalesce::Key("inventory".to_string()));
+        assert_eq!(
+            coalesce_key(&text_popup_event).as_deref(),
+            Some("inventory")
+        );
+    }
+
+    #[test]
+    fn buttons_are_ordered_confirm_extras_dismiss() {
+        let button = |text: &str| TextPopupButton {
+            text: text.to_string(),
+            ..default()
+        };
+        let text_popup_event = TextPopupEvent {
+            confirm_button: Some(button("Yes")),
+            buttons: vec![button("Maybe"), button("Later")],
+            dismiss_button: Some(button("No")),
+            ..default()
+        };
+        let buttons = get_buttons(&text_popup_event)
+            .into_iter()
+            .map(|(button, reason)| (button.text, reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buttons,
+            [
+                ("Yes".to_string(), TextPopupCloseReason::Confirmed),
+                ("Maybe".to_string(), TextPopupCloseReason::Button(1)),
+                ("Later".to_string(), TextPopupCloseReason::Button(2)),
+                ("No".to_string(), TextPopupCloseReason::Dismissed),
+            ]
+        );
+    }
+
+    #[test]
+    fn wizard_shows_back_from_the_second_page_and_finish_on_the_last() {
+        assert_eq!(visible_wizard_buttons(0, 1), [false, false, true]);
+        assert_eq!(visible_wizard_buttons(0, 3), [false, true, false]);
+        assert_eq!(visible_wizard_buttons(1, 3), [true, true, false]);
+        assert_eq!(visible_wizard_buttons(2, 3), [true, false, true]);
+    }
 }